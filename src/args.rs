@@ -1,4 +1,15 @@
+use crate::domain::{LinkKind, LinkOrder, UrlFilter, UrlPattern};
+use crate::service::{
+    DEFAULT_MAX_CLIENT_REDIRECTS, DEFAULT_TIMEOUT_SECS, FetchOptions, NormalizeOptions,
+    TrailingSlash,
+};
+use crate::tui::{
+    DEFAULT_PREFETCH_CONCURRENCY, DEFAULT_PREFETCH_NEIGHBORS, DEFAULT_PREFETCH_PER_HOST,
+    MAX_PREFETCH_NEIGHBORS,
+};
 use clap::Parser;
+use clap::builder::RangedU64ValueParser;
+use std::time::Duration;
 
 /// urll lets you browse URLs in a webpage in a recursive manner
 #[derive(Parser, Debug)]
@@ -8,4 +19,161 @@ pub struct Args {
     // whether to open up results in a TUI
     #[arg(short = 't', long = "tui")]
     pub tui: bool,
+    /// Format to print results in, when not using the TUI
+    #[arg(long = "format", value_name = "FORMAT", default_value = "text")]
+    pub format: OutputFormat,
+    /// Print URLs as they appear on the page instead of their normalized form
+    #[arg(long = "raw")]
+    pub raw: bool,
+    /// Order in which to list URLs
+    #[arg(long = "sort", value_name = "ORDER", default_value = "alphabetical")]
+    pub sort: LinkOrder,
+    /// Prefix each URL with the number of times it appears on the page
+    #[arg(long = "counts")]
+    pub counts: bool,
+    /// Print the redirects followed to get to the page to stderr
+    #[arg(long = "show-redirects")]
+    pub show_redirects: bool,
+    /// Don't follow links with this rel value when browsing recursively in the TUI
+    #[arg(long = "no-follow-rel", value_name = "REL")]
+    pub no_follow_rels: Vec<String>,
+    /// Fetch the pages of the selected URL and its neighbors in the background in the TUI
+    #[arg(long = "prefetch")]
+    pub prefetch: bool,
+    /// Number of URLs above and below the selected one to prefetch
+    #[arg(
+        long = "prefetch-neighbors",
+        value_name = "NUM",
+        default_value_t = DEFAULT_PREFETCH_NEIGHBORS,
+        value_parser = RangedU64ValueParser::<usize>::new().range(..=MAX_PREFETCH_NEIGHBORS as u64)
+    )]
+    pub prefetch_neighbors: usize,
+    /// Maximum number of pages to prefetch at once
+    #[arg(
+        long = "prefetch-concurrency",
+        value_name = "NUM",
+        default_value_t = DEFAULT_PREFETCH_CONCURRENCY,
+        value_parser = RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    pub prefetch_concurrency: usize,
+    /// Maximum number of pages to prefetch at once from a single host
+    #[arg(
+        long = "prefetch-per-host",
+        value_name = "NUM",
+        default_value_t = DEFAULT_PREFETCH_PER_HOST,
+        value_parser = RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    pub prefetch_per_host: usize,
+    #[command(flatten)]
+    pub filter: FilterArgs,
+    #[command(flatten)]
+    pub fetch: FetchArgs,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct FetchArgs {
+    /// Treat URLs that only differ in their fragment as distinct
+    #[arg(long = "keep-fragments")]
+    pub keep_fragments: bool,
+    /// Don't remove tracking query parameters (utm_*, fbclid, gclid, etc.)
+    #[arg(long = "keep-tracking-params")]
+    pub keep_tracking_params: bool,
+    /// How to treat trailing slashes in URL paths
+    #[arg(long = "trailing-slash", value_name = "MODE", default_value = "keep")]
+    pub trailing_slash: TrailingSlash,
+    /// Follow redirects done by pages themselves (meta refresh, window.location)
+    #[arg(long = "follow-client-redirects")]
+    pub follow_client_redirects: bool,
+    /// Maximum number of client redirects to follow
+    #[arg(long = "max-client-redirects", value_name = "NUM", default_value_t = DEFAULT_MAX_CLIENT_REDIRECTS)]
+    pub max_client_redirects: usize,
+    /// Seconds to wait for a page (including redirects) before giving up
+    #[arg(long = "timeout", value_name = "SECS", default_value_t = DEFAULT_TIMEOUT_SECS)]
+    pub timeout: u64,
+}
+
+impl From<&Args> for FetchOptions {
+    fn from(args: &Args) -> Self {
+        FetchOptions {
+            normalize: NormalizeOptions {
+                strip_fragments: !args.fetch.keep_fragments,
+                strip_tracking_params: !args.fetch.keep_tracking_params,
+                trailing_slash: args.fetch.trailing_slash,
+            },
+            follow_client_redirects: args.fetch.follow_client_redirects,
+            max_client_redirects: args.fetch.max_client_redirects,
+            timeout: Duration::from_secs(args.fetch.timeout),
+        }
+    }
+}
+
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct FilterArgs {
+    /// Only show URLs matching this regex (prefix with "glob:" to use a glob instead)
+    #[arg(long = "include", value_name = "PATTERN")]
+    pub include: Vec<UrlPattern>,
+    /// Hide URLs matching this regex (prefix with "glob:" to use a glob instead)
+    #[arg(long = "exclude", value_name = "PATTERN")]
+    pub exclude: Vec<UrlPattern>,
+    /// Only show URLs on this domain (or its subdomains)
+    #[arg(long = "domain", value_name = "DOMAIN")]
+    pub domains: Vec<String>,
+    /// Hide URLs on this domain (or its subdomains)
+    #[arg(long = "exclude-domain", value_name = "DOMAIN")]
+    pub exclude_domains: Vec<String>,
+    /// Only show URLs of this kind
+    #[arg(long = "kind", value_name = "KIND")]
+    pub kinds: Vec<LinkKind>,
+    /// Hide URLs of this kind
+    #[arg(long = "exclude-kind", value_name = "KIND")]
+    pub exclude_kinds: Vec<LinkKind>,
+    /// Only show URLs linked with this rel value (eg. nofollow, sponsored, ugc)
+    #[arg(long = "rel", value_name = "REL")]
+    pub rels: Vec<String>,
+    /// Hide URLs linked with this rel value
+    #[arg(long = "exclude-rel", value_name = "REL")]
+    pub exclude_rels: Vec<String>,
+}
+
+impl From<FilterArgs> for UrlFilter {
+    fn from(args: FilterArgs) -> Self {
+        UrlFilter {
+            include: args.include,
+            exclude: args.exclude,
+            domains: args.domains,
+            exclude_domains: args.exclude_domains,
+            kinds: args.kinds,
+            exclude_kinds: args.exclude_kinds,
+            rels: args.rels,
+            exclude_rels: args.exclude_rels,
+        }
+    }
+}
+
+#[derive(Parser, Debug)]
+#[command(no_binary_name = true)]
+struct FilterSpec {
+    #[command(flatten)]
+    filter: FilterArgs,
+}
+
+/// Parses a filter spec written with the same flags as the command line, eg.
+/// `--include /docs/ --exclude-domain example.com`.
+pub fn parse_filter(spec: &str) -> Result<UrlFilter, String> {
+    FilterSpec::try_parse_from(spec.split_whitespace())
+        .map(|s| s.filter.into())
+        .map_err(|e| {
+            e.to_string()
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .trim_start_matches("error: ")
+                .to_string()
+        })
 }
@@ -0,0 +1,275 @@
+use super::{Link, LinkKind};
+use globset::{Glob, GlobMatcher};
+use regex::Regex;
+use std::str::FromStr;
+use url::Url;
+
+const GLOB_PREFIX: &str = "glob:";
+
+#[derive(Clone, Debug)]
+pub enum UrlPattern {
+    Regex(Regex),
+    Glob { raw: String, matcher: GlobMatcher },
+}
+
+impl UrlPattern {
+    pub fn is_match(&self, url: &str) -> bool {
+        match self {
+            UrlPattern::Regex(re) => re.is_match(url),
+            UrlPattern::Glob { matcher, .. } => matcher.is_match(url),
+        }
+    }
+}
+
+impl FromStr for UrlPattern {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix(GLOB_PREFIX) {
+            Some(raw) => {
+                let glob = Glob::new(raw).map_err(|e| format!("invalid glob pattern: {}", e))?;
+                Ok(UrlPattern::Glob {
+                    raw: raw.to_string(),
+                    matcher: glob.compile_matcher(),
+                })
+            }
+            None => Regex::new(s)
+                .map(UrlPattern::Regex)
+                .map_err(|e| format!("invalid regex: {}", e)),
+        }
+    }
+}
+
+impl std::fmt::Display for UrlPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UrlPattern::Regex(re) => write!(f, "{}", re.as_str()),
+            UrlPattern::Glob { raw, .. } => write!(f, "{}{}", GLOB_PREFIX, raw),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct UrlFilter {
+    pub include: Vec<UrlPattern>,
+    pub exclude: Vec<UrlPattern>,
+    pub domains: Vec<String>,
+    pub exclude_domains: Vec<String>,
+    pub kinds: Vec<LinkKind>,
+    pub exclude_kinds: Vec<LinkKind>,
+    pub rels: Vec<String>,
+    pub exclude_rels: Vec<String>,
+}
+
+impl UrlFilter {
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty()
+            && self.exclude.is_empty()
+            && self.domains.is_empty()
+            && self.exclude_domains.is_empty()
+            && self.kinds.is_empty()
+            && self.exclude_kinds.is_empty()
+            && self.rels.is_empty()
+            && self.exclude_rels.is_empty()
+    }
+
+    pub fn matches(&self, link: &Link) -> bool {
+        if !self.kinds.is_empty() && !self.kinds.contains(&link.kind) {
+            return false;
+        }
+
+        if self.exclude_kinds.contains(&link.kind) {
+            return false;
+        }
+
+        if !self.rels.is_empty() && !self.rels.iter().any(|r| link.has_rel(r)) {
+            return false;
+        }
+
+        if self.exclude_rels.iter().any(|r| link.has_rel(r)) {
+            return false;
+        }
+
+        let url = link.url.as_str();
+        if !self.include.is_empty() && !self.include.iter().any(|p| p.is_match(url)) {
+            return false;
+        }
+
+        if self.exclude.iter().any(|p| p.is_match(url)) {
+            return false;
+        }
+
+        if self.domains.is_empty() && self.exclude_domains.is_empty() {
+            return true;
+        }
+
+        let host = Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(|h| h.to_lowercase()))
+            .unwrap_or_default();
+
+        if !self.domains.is_empty() && !self.domains.iter().any(|d| host_in_domain(&host, d)) {
+            return false;
+        }
+
+        !self
+            .exclude_domains
+            .iter()
+            .any(|d| host_in_domain(&host, d))
+    }
+
+    pub fn apply<'a>(&self, links: &'a [Link]) -> Vec<&'a Link> {
+        links.iter().filter(|l| self.matches(l)).collect()
+    }
+}
+
+impl std::fmt::Display for UrlFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = Vec::new();
+        parts.extend(self.include.iter().map(|p| format!("--include {}", p)));
+        parts.extend(self.exclude.iter().map(|p| format!("--exclude {}", p)));
+        parts.extend(self.domains.iter().map(|d| format!("--domain {}", d)));
+        parts.extend(
+            self.exclude_domains
+                .iter()
+                .map(|d| format!("--exclude-domain {}", d)),
+        );
+        parts.extend(self.kinds.iter().map(|k| format!("--kind {}", k)));
+        parts.extend(
+            self.exclude_kinds
+                .iter()
+                .map(|k| format!("--exclude-kind {}", k)),
+        );
+        parts.extend(self.rels.iter().map(|r| format!("--rel {}", r)));
+        parts.extend(
+            self.exclude_rels
+                .iter()
+                .map(|r| format!("--exclude-rel {}", r)),
+        );
+
+        write!(f, "{}", parts.join(" "))
+    }
+}
+
+fn host_in_domain(host: &str, domain: &str) -> bool {
+    let domain = domain.trim_start_matches('.').to_lowercase();
+    host == domain || host.ends_with(&format!(".{}", domain))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern(s: &str) -> UrlPattern {
+        s.parse().unwrap()
+    }
+
+    fn matches(filter: &UrlFilter, url: &str) -> bool {
+        filter.matches(&Link::from_url(url))
+    }
+
+    fn link_of_kind(kind: LinkKind) -> Link {
+        Link {
+            kind,
+            ..Link::from_url("https://example.com/")
+        }
+    }
+
+    #[test]
+    fn patterns_are_regexes_unless_prefixed_with_glob() {
+        assert!(pattern("/docs/v[0-9]+/").is_match("https://example.com/docs/v2/"));
+        assert!(!pattern("/docs/v[0-9]+/").is_match("https://example.com/docs/latest/"));
+        assert!(pattern("glob:*.pdf").is_match("https://example.com/files/a.pdf"));
+        assert!(!pattern("glob:*.pdf").is_match("https://example.com/a.pdf.html"));
+        assert_eq!(pattern("glob:*.pdf").to_string(), "glob:*.pdf");
+    }
+
+    #[test]
+    fn invalid_patterns_are_rejected() {
+        assert!("(unclosed".parse::<UrlPattern>().is_err());
+        assert!("glob:[unclosed".parse::<UrlPattern>().is_err());
+    }
+
+    #[test]
+    fn urls_must_match_an_include_and_no_exclude() {
+        let filter = UrlFilter {
+            include: vec![pattern("/docs/"), pattern("/blog/")],
+            exclude: vec![pattern("draft")],
+            ..UrlFilter::default()
+        };
+
+        assert!(matches(&filter, "https://example.com/docs/intro"));
+        assert!(matches(&filter, "https://example.com/blog/post"));
+        assert!(!matches(&filter, "https://example.com/about"));
+        assert!(!matches(&filter, "https://example.com/blog/draft-post"));
+    }
+
+    #[test]
+    fn domains_include_their_subdomains() {
+        let filter = UrlFilter {
+            domains: vec![".Example.com".to_string()],
+            exclude_domains: vec!["ads.example.com".to_string()],
+            ..UrlFilter::default()
+        };
+
+        assert!(matches(&filter, "https://example.com/"));
+        assert!(matches(&filter, "https://DOCS.example.com/a"));
+        assert!(!matches(&filter, "https://ads.example.com/x"));
+        assert!(!matches(&filter, "https://notexample.com/"));
+        assert!(!matches(&filter, "https://example.com.evil.org/"));
+    }
+
+    #[test]
+    fn links_must_be_of_an_included_kind_and_no_excluded_one() {
+        let filter = UrlFilter {
+            kinds: vec![LinkKind::Internal, LinkKind::Subdomain],
+            exclude_kinds: vec![LinkKind::Subdomain],
+            ..UrlFilter::default()
+        };
+
+        assert!(filter.matches(&link_of_kind(LinkKind::Internal)));
+        assert!(!filter.matches(&link_of_kind(LinkKind::Subdomain)));
+        assert!(!filter.matches(&link_of_kind(LinkKind::External)));
+    }
+
+    #[test]
+    fn links_must_have_an_included_rel_and_no_excluded_one() {
+        let filter = UrlFilter {
+            rels: vec!["author".to_string(), "me".to_string()],
+            exclude_rels: vec!["nofollow".to_string()],
+            ..UrlFilter::default()
+        };
+        let link = |rel: &[&str]| Link {
+            rel: rel.iter().map(|r| r.to_string()).collect(),
+            ..Link::from_url("https://example.com/")
+        };
+
+        assert!(filter.matches(&link(&["me"])));
+        assert!(filter.matches(&link(&["Author", "external"])));
+        assert!(!filter.matches(&link(&["author", "nofollow"])));
+        assert!(!filter.matches(&link(&[])));
+    }
+
+    #[test]
+    fn empty_filters_match_everything() {
+        let filter = UrlFilter::default();
+
+        assert!(filter.is_empty());
+        assert!(matches(&filter, "https://example.com/"));
+        assert!(matches(&filter, "not a url"));
+    }
+
+    #[test]
+    fn filters_are_displayed_as_flags() {
+        let filter = UrlFilter {
+            include: vec![pattern("/docs/")],
+            exclude_domains: vec!["example.org".to_string()],
+            ..UrlFilter::default()
+        };
+
+        assert_eq!(
+            filter.to_string(),
+            "--include /docs/ --exclude-domain example.org"
+        );
+    }
+}
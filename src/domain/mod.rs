@@ -1,3 +1,7 @@
+mod filter;
+mod order;
 mod page;
 
+pub use filter::*;
+pub use order::*;
 pub use page::*;
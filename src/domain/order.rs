@@ -0,0 +1,144 @@
+use super::Link;
+use clap::ValueEnum;
+use url::Url;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum LinkOrder {
+    Document,
+    #[default]
+    Alphabetical,
+    Host,
+    Count,
+}
+
+impl LinkOrder {
+    pub fn next(&self) -> Self {
+        match self {
+            LinkOrder::Document => LinkOrder::Alphabetical,
+            LinkOrder::Alphabetical => LinkOrder::Host,
+            LinkOrder::Host => LinkOrder::Count,
+            LinkOrder::Count => LinkOrder::Document,
+        }
+    }
+
+    /// Sorts links that are in document order; ties keep their relative
+    /// position on the page.
+    pub fn sort(&self, links: &mut [&Link]) {
+        match self {
+            LinkOrder::Document => {}
+            LinkOrder::Alphabetical => links.sort_by(|a, b| a.url.cmp(&b.url)),
+            LinkOrder::Host => links.sort_by_cached_key(|l| {
+                Url::parse(&l.url)
+                    .ok()
+                    .and_then(|u| u.host_str().map(|h| h.to_string()))
+                    .unwrap_or_default()
+            }),
+            LinkOrder::Count => links.sort_by_key(|l| std::cmp::Reverse(l.count)),
+        }
+    }
+}
+
+impl std::fmt::Display for LinkOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LinkOrder::Document => write!(f, "document"),
+            LinkOrder::Alphabetical => write!(f, "alphabetical"),
+            LinkOrder::Host => write!(f, "host"),
+            LinkOrder::Count => write!(f, "count"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(order: LinkOrder, links: &[Link]) -> Vec<&str> {
+        let mut links: Vec<&Link> = links.iter().collect();
+        order.sort(&mut links);
+        links.iter().map(|l| l.url.as_str()).collect()
+    }
+
+    fn links() -> Vec<Link> {
+        vec![
+            Link {
+                count: 2,
+                ..Link::from_url("https://b.com/2")
+            },
+            Link::from_url("https://a.com/1"),
+            Link {
+                count: 3,
+                ..Link::from_url("https://b.com/1")
+            },
+            Link {
+                count: 2,
+                ..Link::from_url("https://a.com/2")
+            },
+        ]
+    }
+
+    #[test]
+    fn document_order_is_left_as_is() {
+        assert_eq!(
+            sorted(LinkOrder::Document, &links()),
+            [
+                "https://b.com/2",
+                "https://a.com/1",
+                "https://b.com/1",
+                "https://a.com/2"
+            ]
+        );
+    }
+
+    #[test]
+    fn alphabetical_order_sorts_by_url() {
+        assert_eq!(
+            sorted(LinkOrder::Alphabetical, &links()),
+            [
+                "https://a.com/1",
+                "https://a.com/2",
+                "https://b.com/1",
+                "https://b.com/2"
+            ]
+        );
+    }
+
+    #[test]
+    fn host_order_keeps_document_order_within_a_host() {
+        assert_eq!(
+            sorted(LinkOrder::Host, &links()),
+            [
+                "https://a.com/1",
+                "https://a.com/2",
+                "https://b.com/2",
+                "https://b.com/1"
+            ]
+        );
+    }
+
+    #[test]
+    fn count_order_puts_the_most_frequent_first() {
+        assert_eq!(
+            sorted(LinkOrder::Count, &links()),
+            [
+                "https://b.com/1",
+                "https://b.com/2",
+                "https://a.com/2",
+                "https://a.com/1"
+            ]
+        );
+    }
+
+    #[test]
+    fn next_cycles_through_every_order() {
+        let mut order = LinkOrder::Document;
+        let mut seen = vec![];
+        for _ in 0..LinkOrder::value_variants().len() {
+            seen.push(order);
+            order = order.next();
+        }
+
+        assert_eq!(order, LinkOrder::Document);
+        assert_eq!(seen, LinkOrder::value_variants());
+    }
+}
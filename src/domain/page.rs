@@ -1,11 +1,347 @@
+use super::{LinkOrder, UrlFilter};
+use clap::ValueEnum;
+use serde::Serialize;
+use std::time::Duration;
+
 pub struct Page {
     pub details: PageDetails,
-    pub page_urls: Vec<String>,
+    /// links in the order they first appear on the page
+    pub links: Vec<Link>,
+    pub response: ResponseInfo,
+    pub reader: Reader,
+    /// the HTML the page was parsed from
+    pub source: String,
+}
+
+impl Page {
+    pub fn visible_links(&self, filter: &UrlFilter, order: LinkOrder) -> Vec<&Link> {
+        let mut links = filter.apply(&self.links);
+        order.sort(&mut links);
+        // redirects are what the page is about, so they always go first
+        links.sort_by_key(|l| l.source == LinkSource::Anchor);
+        links
+    }
+}
+
+/// Readable content of a page, with the links in it numbered in the order they
+/// first appear.
+#[derive(Clone, Debug, Default)]
+pub struct Reader {
+    pub blocks: Vec<ReaderBlock>,
+    /// normalized URLs; link number `n` points to `links[n - 1]`
+    pub links: Vec<String>,
+}
+
+impl Reader {
+    pub fn link(&self, number: usize) -> Option<&str> {
+        self.links
+            .get(number.checked_sub(1)?)
+            .map(|url| url.as_str())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ReaderBlock {
+    pub kind: ReaderBlockKind,
+    pub spans: Vec<ReaderSpan>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReaderBlockKind {
+    Heading(u8),
+    Paragraph,
+    ListItem,
+    Quote,
+    Preformatted,
+}
+
+#[derive(Clone, Debug)]
+pub enum ReaderSpan {
+    Text(String),
+    Link { text: String, number: usize },
+}
+
+/// Details of the HTTP response the page was read from (ie, the last one, after
+/// following redirects).
+#[derive(Clone, Debug)]
+pub struct ResponseInfo {
+    pub status: u16,
+    /// in the order they were received; names are lowercase
+    pub headers: Vec<(String, String)>,
+    /// time until the response headers were received
+    pub ttfb: Duration,
+    /// time until the body was fully read
+    pub total: Duration,
+    /// size of the body, after decoding it as text
+    pub body_size: usize,
 }
 
-#[derive(Clone)]
+impl ResponseInfo {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+#[derive(Clone, Serialize)]
 pub struct PageDetails {
     pub url: String,
     pub title: Option<String>,
     pub description: Option<String>,
+    pub canonical_url: Option<String>,
+    pub language: Option<String>,
+    pub robots: Option<String>,
+    pub image: Option<String>,
+    pub og_type: Option<String>,
+    pub site_name: Option<String>,
+    pub favicon: Option<String>,
+    pub twitter: TwitterCard,
+    pub json_ld: Vec<serde_json::Value>,
+    /// a redirect performed by the page itself, that wasn't followed
+    pub client_redirect: Option<ClientRedirect>,
+    /// redirects followed to get to the page, in order
+    pub redirects: Vec<RedirectHop>,
+}
+
+impl PageDetails {
+    /// URL the page was finally fetched from, after following redirects.
+    pub fn final_url(&self) -> &str {
+        self.redirects
+            .last()
+            .map(|hop| hop.location.as_str())
+            .unwrap_or(&self.url)
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct RedirectHop {
+    pub url: String,
+    pub status: u16,
+    pub location: String,
+    /// set when the redirect was performed by the page itself rather than via
+    /// an HTTP response
+    pub client: Option<ClientRedirectKind>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ClientRedirect {
+    pub url: String,
+    pub kind: ClientRedirectKind,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClientRedirectKind {
+    MetaRefresh,
+    Script,
+}
+
+impl std::fmt::Display for ClientRedirectKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClientRedirectKind::MetaRefresh => write!(f, "meta refresh"),
+            ClientRedirectKind::Script => write!(f, "script"),
+        }
+    }
+}
+
+#[derive(Clone, Default, Serialize)]
+pub struct TwitterCard {
+    pub card: Option<String>,
+    pub site: Option<String>,
+    pub creator: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub image: Option<String>,
+}
+
+impl TwitterCard {
+    pub fn is_empty(&self) -> bool {
+        self.card.is_none()
+            && self.site.is_none()
+            && self.creator.is_none()
+            && self.title.is_none()
+            && self.description.is_none()
+            && self.image.is_none()
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Link {
+    /// normalized URL; used for deduplication and navigation
+    pub url: String,
+    /// URL as resolved from the page, before normalization
+    pub raw: String,
+    /// text of the first anchor pointing to the link that has any
+    pub text: Option<String>,
+    /// number of times the link appears on the page
+    pub count: usize,
+    pub kind: LinkKind,
+    /// lowercased tokens from the `rel` attribute of every anchor pointing to
+    /// the link
+    pub rel: Vec<String>,
+    pub source: LinkSource,
+    /// snippet of the text around the first anchor pointing to the link
+    pub context: Option<String>,
+    /// the heading the first anchor pointing to the link is under
+    pub heading: Option<String>,
+}
+
+/// How the page points to a link.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkSource {
+    Anchor,
+    /// the page redirects to the link on its own
+    ClientRedirect,
+}
+
+impl Link {
+    pub fn has_rel(&self, token: &str) -> bool {
+        self.rel.iter().any(|r| r.eq_ignore_ascii_case(token))
+    }
+}
+
+/// Where a link points to, relative to the page it was found on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkKind {
+    // a fragment on the same page
+    Fragment,
+    // a file that's likely to be downloaded rather than browsed
+    Download,
+    // same host as the page
+    Internal,
+    // a different host on the same registrable domain
+    Subdomain,
+    External,
+}
+
+impl LinkKind {
+    pub fn marker(&self) -> char {
+        match self {
+            LinkKind::Fragment => '#',
+            LinkKind::Download => 'D',
+            LinkKind::Internal => 'I',
+            LinkKind::Subdomain => 'S',
+            LinkKind::External => 'E',
+        }
+    }
+}
+
+impl std::fmt::Display for LinkKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LinkKind::Fragment => write!(f, "fragment"),
+            LinkKind::Download => write!(f, "download"),
+            LinkKind::Internal => write!(f, "internal"),
+            LinkKind::Subdomain => write!(f, "subdomain"),
+            LinkKind::External => write!(f, "external"),
+        }
+    }
+}
+
+#[cfg(test)]
+impl Page {
+    /// A page with nothing on it but `links`.
+    pub(crate) fn with_links(url: &str, links: Vec<Link>) -> Self {
+        Self {
+            details: PageDetails {
+                url: url.to_string(),
+                title: None,
+                description: None,
+                canonical_url: None,
+                language: None,
+                robots: None,
+                image: None,
+                og_type: None,
+                site_name: None,
+                favicon: None,
+                twitter: TwitterCard::default(),
+                json_ld: vec![],
+                client_redirect: None,
+                redirects: vec![],
+            },
+            links,
+            response: ResponseInfo {
+                status: 200,
+                headers: vec![],
+                ttfb: Duration::ZERO,
+                total: Duration::ZERO,
+                body_size: 0,
+            },
+            reader: Reader::default(),
+            source: String::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+impl Link {
+    /// A link that appears once on a page, with nothing known about it but its
+    /// URL.
+    pub(crate) fn from_url(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            raw: url.to_string(),
+            text: None,
+            count: 1,
+            kind: LinkKind::External,
+            rel: vec![],
+            source: LinkSource::Anchor,
+            context: None,
+            heading: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn details(url: &str) -> PageDetails {
+        Page::with_links(url, vec![]).details
+    }
+
+    fn hop(url: &str, location: &str) -> RedirectHop {
+        RedirectHop {
+            url: url.to_string(),
+            status: 301,
+            location: location.to_string(),
+            client: None,
+        }
+    }
+
+    #[test]
+    fn final_url_is_where_the_last_redirect_points_to() {
+        let mut details = details("https://example.com/");
+        assert_eq!(details.final_url(), "https://example.com/");
+
+        details.redirects = vec![
+            hop("https://example.com/", "https://www.example.com/"),
+            hop("https://www.example.com/", "https://www.example.com/home"),
+        ];
+        assert_eq!(details.final_url(), "https://www.example.com/home");
+    }
+
+    #[test]
+    fn headers_are_looked_up_ignoring_case() {
+        let response = ResponseInfo {
+            status: 200,
+            headers: vec![
+                ("content-type".to_string(), "text/html".to_string()),
+                ("set-cookie".to_string(), "a=1".to_string()),
+                ("set-cookie".to_string(), "b=2".to_string()),
+            ],
+            ttfb: Duration::ZERO,
+            total: Duration::ZERO,
+            body_size: 0,
+        };
+
+        assert_eq!(response.header("Content-Type"), Some("text/html"));
+        assert_eq!(response.header("set-cookie"), Some("a=1"));
+        assert_eq!(response.header("server"), None);
+    }
 }
@@ -3,27 +3,84 @@ mod domain;
 mod service;
 mod tui;
 
-use args::Args;
+use args::{Args, OutputFormat};
 use clap::Parser;
-use service::fetch_urls;
-use tui::run_tui;
+use domain::{Link, PageDetails, UrlFilter};
+use serde::Serialize;
+use service::{FetchOptions, fetch_urls};
+use tui::{PrefetchOptions, TuiConfig, run_tui};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     env_logger::init();
     let args = Args::parse();
+    let fetch_options = FetchOptions::from(&args);
+    let filter = UrlFilter::from(args.filter);
 
-    let page = fetch_urls(&args.url).await?;
-    if page.page_urls.is_empty() {
-        return Ok(());
-    }
+    let page = fetch_urls(&args.url, &fetch_options).await?;
 
     match args.tui {
-        true => run_tui(page).await?,
+        true => {
+            if page.links.is_empty() {
+                eprintln!("no URLs found on {}", page.details.url);
+                return Ok(());
+            }
+
+            let config = TuiConfig {
+                filter,
+                order: args.sort,
+                fetch_options,
+                no_follow_rels: args.no_follow_rels,
+                prefetch: PrefetchOptions {
+                    enabled: args.prefetch,
+                    neighbors: args.prefetch_neighbors,
+                    concurrency: args.prefetch_concurrency,
+                    per_host: args.prefetch_per_host,
+                },
+            };
+            run_tui(page, config).await?
+        }
         false => {
-            println!("{}", page.page_urls.join("\n"));
+            if args.show_redirects {
+                for hop in &page.details.redirects {
+                    let via = match &hop.client {
+                        Some(kind) => kind.to_string(),
+                        None => hop.status.to_string(),
+                    };
+                    eprintln!("{}\t{} -> {}", via, hop.url, hop.location);
+                }
+            }
+
+            let links = page.visible_links(&filter, args.sort);
+            match args.format {
+                OutputFormat::Text => {
+                    for link in links {
+                        let url = match args.raw {
+                            true => &link.raw,
+                            false => &link.url,
+                        };
+                        match args.counts {
+                            true => println!("{}\t{}", link.count, url),
+                            false => println!("{}", url),
+                        }
+                    }
+                }
+                OutputFormat::Json => {
+                    let output = JsonOutput {
+                        page: &page.details,
+                        links,
+                    };
+                    println!("{}", serde_json::to_string_pretty(&output)?);
+                }
+            }
         }
     }
 
     Ok(())
 }
+
+#[derive(Serialize)]
+struct JsonOutput<'a> {
+    page: &'a PageDetails,
+    links: Vec<&'a Link>,
+}
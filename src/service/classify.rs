@@ -0,0 +1,130 @@
+use crate::domain::LinkKind;
+use url::Url;
+
+const DOWNLOAD_EXTENSIONS: [&str; 36] = [
+    "7z", "apk", "avi", "bz2", "csv", "deb", "dmg", "doc", "docx", "epub", "exe", "flac", "gz",
+    "iso", "jar", "m4a", "mkv", "mov", "mp3", "mp4", "msi", "odp", "ods", "odt", "pdf", "pkg",
+    "ppt", "pptx", "rar", "rpm", "tar", "tgz", "wav", "xls", "xlsx", "zip",
+];
+
+/// Classifies `link` relative to `page`. `link` is expected to still have its
+/// fragment, ie. it shouldn't have been normalized yet.
+pub fn classify_link(page: &Url, link: &Url) -> LinkKind {
+    if link.fragment().is_some() && without_fragment(link) == without_fragment(page) {
+        return LinkKind::Fragment;
+    }
+
+    let is_download = link
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .and_then(|last| last.rsplit_once('.'))
+        .is_some_and(|(_, ext)| DOWNLOAD_EXTENSIONS.contains(&ext.to_lowercase().as_str()));
+    if is_download {
+        return LinkKind::Download;
+    }
+
+    let (Some(page_host), Some(link_host)) = (page.host_str(), link.host_str()) else {
+        return LinkKind::External;
+    };
+
+    if page_host == link_host {
+        return LinkKind::Internal;
+    }
+
+    match (psl::domain_str(page_host), psl::domain_str(link_host)) {
+        (Some(page_domain), Some(link_domain)) if page_domain == link_domain => LinkKind::Subdomain,
+        _ => LinkKind::External,
+    }
+}
+
+fn without_fragment(url: &Url) -> Url {
+    let mut url = url.clone();
+    url.set_fragment(None);
+    url
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classify(page: &str, link: &str) -> LinkKind {
+        classify_link(&Url::parse(page).unwrap(), &Url::parse(link).unwrap())
+    }
+
+    #[test]
+    fn fragments_on_the_same_page_are_detected() {
+        let page = "https://example.com/docs?v=1#intro";
+        assert_eq!(
+            classify(page, "https://example.com/docs?v=1#usage"),
+            LinkKind::Fragment
+        );
+        // a different page, or the same page without a fragment
+        assert_eq!(
+            classify(page, "https://example.com/docs?v=2#usage"),
+            LinkKind::Internal
+        );
+        assert_eq!(
+            classify(page, "https://example.com/docs?v=1"),
+            LinkKind::Internal
+        );
+    }
+
+    #[test]
+    fn downloads_are_detected_by_extension() {
+        let page = "https://example.com/";
+        assert_eq!(
+            classify(page, "https://cdn.other.org/files/Report.PDF"),
+            LinkKind::Download
+        );
+        assert_eq!(
+            classify(page, "https://example.com/release.tar.gz?mirror=1"),
+            LinkKind::Download
+        );
+        assert_eq!(
+            classify(page, "https://example.com/pdf/"),
+            LinkKind::Internal
+        );
+        assert_eq!(
+            classify(page, "https://example.com/index.html"),
+            LinkKind::Internal
+        );
+    }
+
+    #[test]
+    fn hosts_are_compared_by_registrable_domain() {
+        let page = "https://docs.example.com/";
+        assert_eq!(
+            classify(page, "https://docs.example.com/a"),
+            LinkKind::Internal
+        );
+        assert_eq!(
+            classify(page, "https://blog.example.com/"),
+            LinkKind::Subdomain
+        );
+        assert_eq!(classify(page, "https://example.com/"), LinkKind::Subdomain);
+        assert_eq!(classify(page, "https://example.org/"), LinkKind::External);
+    }
+
+    #[test]
+    fn hosts_under_multi_label_suffixes_are_compared_by_registrable_domain() {
+        let page = "https://www.example.co.uk/";
+        assert_eq!(
+            classify(page, "https://shop.example.co.uk/"),
+            LinkKind::Subdomain
+        );
+        // different registrable domains under the same public suffix
+        assert_eq!(classify(page, "https://other.co.uk/"), LinkKind::External);
+        assert_eq!(
+            classify("https://alice.github.io/", "https://bob.github.io/"),
+            LinkKind::External
+        );
+    }
+
+    #[test]
+    fn links_without_hosts_are_external() {
+        assert_eq!(
+            classify("https://example.com/", "mailto:someone@example.com"),
+            LinkKind::External
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use super::text::element_text;
+use scraper::ElementRef;
+
+// elements whose text is taken as the context of the links inside them
+const CONTEXT_ELEMENTS: [&str; 10] = [
+    "li",
+    "p",
+    "td",
+    "th",
+    "dd",
+    "dt",
+    "blockquote",
+    "figcaption",
+    "caption",
+    "summary",
+];
+
+// elements whose text is split into sentences, with only the one holding the
+// link being kept
+const PROSE_ELEMENTS: [&str; 3] = ["p", "blockquote", "dd"];
+
+const MAX_CONTEXT_CHARS: usize = 200;
+
+/// Returns a snippet of the text surrounding an anchor: the sentence or list
+/// item (or similar block) it's in.
+///
+/// Nothing is returned when the snippet wouldn't add anything to the anchor's
+/// own text.
+pub(super) fn link_context(anchor: &ElementRef, anchor_text: Option<&str>) -> Option<String> {
+    let block = anchor
+        .ancestors()
+        .filter_map(ElementRef::wrap)
+        .find(|element| CONTEXT_ELEMENTS.contains(&element.value().name()))?;
+
+    let text = element_text(&block);
+    let snippet = match (PROSE_ELEMENTS.contains(&block.value().name()), anchor_text) {
+        (true, Some(anchor_text)) => sentences(&text)
+            .find(|sentence| sentence.contains(anchor_text))
+            .unwrap_or(&text),
+        _ => &text,
+    };
+
+    if snippet.is_empty() || Some(snippet) == anchor_text {
+        return None;
+    }
+
+    Some(truncate(snippet, MAX_CONTEXT_CHARS))
+}
+
+// a sentence ends with ".", "!" or "?" followed by whitespace; this doesn't
+// handle abbreviations, which is fine for a snippet
+fn sentences(text: &str) -> impl Iterator<Item = &str> {
+    let mut rest = text;
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+
+        let end = rest
+            .char_indices()
+            .zip(rest.chars().skip(1))
+            .find(|((_, c), next)| matches!(c, '.' | '!' | '?') && next.is_whitespace())
+            .map(|((i, c), _)| i + c.len_utf8())
+            .unwrap_or(rest.len());
+        let sentence = &rest[..end];
+        rest = rest[end..].trim_start();
+
+        Some(sentence)
+    })
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((i, _)) => format!("{}…", text[..i].trim_end()),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scraper::{Html, Selector};
+
+    fn first_link_context(html: &str, anchor_text: Option<&str>) -> Option<String> {
+        let document = Html::parse_fragment(html);
+        let selector = Selector::parse("a").unwrap();
+        link_context(&document.select(&selector).next().unwrap(), anchor_text)
+    }
+
+    #[test]
+    fn prose_context_is_the_sentence_holding_the_link() {
+        assert_eq!(
+            first_link_context(
+                "<p>First sentence. Read <a href='/'>the guide</a> first! Last one?</p>",
+                Some("the guide"),
+            )
+            .as_deref(),
+            Some("Read the guide first!")
+        );
+    }
+
+    #[test]
+    fn list_item_context_is_the_whole_item() {
+        assert_eq!(
+            first_link_context(
+                "<ul><li><a href='/'>Setup</a>. Installing the tools.</li></ul>",
+                Some("Setup"),
+            )
+            .as_deref(),
+            Some("Setup. Installing the tools.")
+        );
+    }
+
+    #[test]
+    fn context_is_skipped_when_it_adds_nothing() {
+        assert_eq!(
+            first_link_context("<li> <a href='/'>Home</a> </li>", Some("Home")),
+            None
+        );
+        assert_eq!(
+            first_link_context("<div>Text <a href='/'>Home</a></div>", Some("Home")),
+            None
+        );
+    }
+
+    #[test]
+    fn sentences_are_split_on_terminal_punctuation_followed_by_whitespace() {
+        assert_eq!(
+            sentences("One. Two! Three? v1.2 is out.").collect::<Vec<_>>(),
+            ["One.", "Two!", "Three?", "v1.2 is out."]
+        );
+        assert_eq!(sentences("").count(), 0);
+    }
+
+    #[test]
+    fn long_snippets_are_truncated_on_a_char_boundary() {
+        assert_eq!(truncate("short", 10), "short");
+        assert_eq!(truncate("héllo wörld", 6), "héllo…");
+        assert_eq!(truncate("abcdef", 6), "abcdef");
+    }
+}
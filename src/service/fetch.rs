@@ -1,62 +1,228 @@
-use crate::domain::{Page, PageDetails};
-use reqwest::get;
+use super::classify::classify_link;
+use super::context::link_context;
+use super::http::{build_client, get_following_redirects};
+use super::metadata::extract_details;
+use super::normalize::{NormalizeOptions, normalize_url};
+use super::reader::extract_reader;
+use super::redirect::detect_client_redirect;
+use super::text::{element_text, link_text};
+use crate::domain::{Link, LinkSource, Page, RedirectHop};
+use reqwest::Client;
 use scraper::{Html, Selector};
+use std::collections::HashMap;
+use std::time::Duration;
 use url::Url;
 
-pub async fn fetch_urls(url: &str) -> anyhow::Result<Page> {
-    let base_url = Url::parse(url)?;
-    let body = get(url).await?.text().await?;
+pub const DEFAULT_MAX_CLIENT_REDIRECTS: usize = 5;
+pub const DEFAULT_TIMEOUT_SECS: u64 = 30;
 
-    let document = Html::parse_document(&body);
+#[derive(Clone, Debug)]
+pub struct FetchOptions {
+    pub normalize: NormalizeOptions,
+    pub follow_client_redirects: bool,
+    pub max_client_redirects: usize,
+    /// for fetching a page, including all the redirects followed to get to it
+    pub timeout: Duration,
+}
 
-    #[allow(clippy::unwrap_used)]
-    let link_selector = Selector::parse("a").unwrap();
+impl Default for FetchOptions {
+    fn default() -> Self {
+        Self {
+            normalize: NormalizeOptions::default(),
+            follow_client_redirects: false,
+            max_client_redirects: DEFAULT_MAX_CLIENT_REDIRECTS,
+            timeout: Duration::from_secs(DEFAULT_TIMEOUT_SECS),
+        }
+    }
+}
 
-    #[allow(clippy::unwrap_used)]
-    let title_selector = Selector::parse("title").unwrap();
+/// Returned (wrapped in an [`anyhow::Error`]) by [`fetch_urls`] when a page
+/// takes longer than the configured timeout to fetch.
+#[derive(Debug)]
+pub struct FetchTimedOut(pub Duration);
 
-    #[allow(clippy::unwrap_used)]
-    let og_title_selector = Selector::parse(r#"meta[property="og:title"]"#).unwrap();
+impl std::fmt::Display for FetchTimedOut {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "timed out after {}s", self.0.as_secs_f32())
+    }
+}
 
+impl std::error::Error for FetchTimedOut {}
+
+/// Fetches a page and extracts its links, optionally following redirects
+/// performed by the page itself (eg. via meta refresh).
+///
+/// When redirects aren't followed (or the redirect limit is hit, or a loop is
+/// detected) the redirect target is surfaced as the first link of the page.
+///
+/// The returned page is keyed by `url`; every redirect (HTTP or client side)
+/// followed to get to it is recorded in its details.
+pub async fn fetch_urls(url: &str, options: &FetchOptions) -> anyhow::Result<Page> {
+    tokio::time::timeout(
+        options.timeout,
+        fetch_following_client_redirects(url, options),
+    )
+    .await
+    .map_err(|_| FetchTimedOut(options.timeout))?
+}
+
+async fn fetch_following_client_redirects(
+    url: &str,
+    options: &FetchOptions,
+) -> anyhow::Result<Page> {
+    let client = build_client()?;
+    let mut current = url.to_string();
+    let mut visited = Vec::new();
+    let mut redirects = Vec::new();
+    loop {
+        let mut page = fetch_page(&client, &current, options).await?;
+        redirects.append(&mut page.details.redirects);
+        visited.push(current);
+
+        match page.details.client_redirect.take() {
+            Some(redirect)
+                if options.follow_client_redirects
+                    && visited.len() <= options.max_client_redirects
+                    && !visited.contains(&redirect.url) =>
+            {
+                redirects.push(RedirectHop {
+                    url: page.details.final_url().to_string(),
+                    status: page.response.status,
+                    location: redirect.url.clone(),
+                    client: Some(redirect.kind),
+                });
+                current = redirect.url;
+            }
+            redirect => {
+                page.details.client_redirect = redirect;
+                page.details.url = url.to_string();
+                page.details.redirects = redirects;
+                return Ok(page);
+            }
+        }
+    }
+}
+
+async fn fetch_page(client: &Client, url: &str, options: &FetchOptions) -> anyhow::Result<Page> {
+    let response = get_following_redirects(client, url).await?;
+    let base_url = response.url;
+
+    let document = Html::parse_document(&response.body);
+
+    // headings are selected along with anchors so that the heading each anchor
+    // is under is known when getting to it
     #[allow(clippy::unwrap_used)]
-    let og_description_selector = Selector::parse(r#"meta[property="og:description"]"#).unwrap();
-
-    let og_title = document
-        .select(&og_title_selector)
-        .next()
-        .and_then(|element| element.value().attr("content"))
-        .map(|s| s.trim().to_string());
-
-    let description = document
-        .select(&og_description_selector)
-        .next()
-        .and_then(|element| element.value().attr("content"))
-        .map(|s| s.trim().to_string());
-
-    let title = og_title.or_else(|| {
-        document
-            .select(&title_selector)
-            .next()
-            .map(|element| element.inner_html().trim().to_string())
-    });
-
-    let mut page_urls = Vec::new();
+    let link_selector = Selector::parse("a, h1, h2, h3, h4, h5, h6").unwrap();
+
+    let mut links: Vec<Link> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    let mut heading: Option<String> = None;
     for element in document.select(&link_selector) {
+        if element.value().name() != "a" {
+            heading = Some(element_text(&element)).filter(|h| !h.is_empty());
+            continue;
+        }
+
         if let Some(href) = element.value().attr("href") {
             let link = base_url.join(href)?;
             if link.as_str().starts_with("https://") {
-                page_urls.push(link.to_string());
+                let normalized = normalize_url(&link, &options.normalize).to_string();
+                let rel = rel_tokens(element.value().attr("rel"));
+                let text = link_text(&element);
+                match positions.get(&normalized).and_then(|&p| links.get_mut(p)) {
+                    Some(existing) => {
+                        existing.count += 1;
+                        if existing.text.is_none() {
+                            existing.text = text;
+                        }
+                        for token in rel {
+                            if !existing.rel.contains(&token) {
+                                existing.rel.push(token);
+                            }
+                        }
+                    }
+                    None => {
+                        positions.insert(normalized.clone(), links.len());
+                        links.push(Link {
+                            url: normalized,
+                            raw: link.to_string(),
+                            context: link_context(&element, text.as_deref()),
+                            heading: heading.clone(),
+                            text,
+                            count: 1,
+                            kind: classify_link(&base_url, &link),
+                            rel,
+                            source: LinkSource::Anchor,
+                        });
+                    }
+                }
             }
         }
     }
-    page_urls.sort();
-    page_urls.dedup();
 
-    let details = PageDetails {
-        url: url.to_string(),
-        title,
-        description,
-    };
+    let mut details = extract_details(url, &base_url, &document);
+    details.client_redirect = detect_client_redirect(&base_url, &document);
+    details.redirects = response.redirects;
+
+    if let Some(redirect) = &details.client_redirect
+        && let Ok(target) = Url::parse(&redirect.url)
+    {
+        let normalized = normalize_url(&target, &options.normalize).to_string();
+        let link = match positions.get(&normalized) {
+            Some(&position) => links.remove(position),
+            None => Link {
+                url: normalized,
+                raw: target.to_string(),
+                text: None,
+                count: 1,
+                kind: classify_link(&base_url, &target),
+                rel: Vec::new(),
+                source: LinkSource::ClientRedirect,
+                context: None,
+                heading: None,
+            },
+        };
+        links.insert(
+            0,
+            Link {
+                source: LinkSource::ClientRedirect,
+                ..link
+            },
+        );
+    }
 
-    Ok(Page { details, page_urls })
+    Ok(Page {
+        details,
+        links,
+        response: response.info,
+        reader: extract_reader(&base_url, &document, &options.normalize),
+        source: response.body,
+    })
+}
+
+fn rel_tokens(rel: Option<&str>) -> Vec<String> {
+    let mut tokens: Vec<String> = Vec::new();
+    for token in rel.unwrap_or_default().split_ascii_whitespace() {
+        let token = token.to_lowercase();
+        if !tokens.contains(&token) {
+            tokens.push(token);
+        }
+    }
+
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rel_tokens_are_lowercased_and_deduplicated() {
+        assert_eq!(
+            rel_tokens(Some(" NoFollow  noopener\tnofollow ")),
+            ["nofollow", "noopener"]
+        );
+        assert!(rel_tokens(Some("")).is_empty());
+        assert!(rel_tokens(None).is_empty());
+    }
 }
@@ -0,0 +1,87 @@
+use crate::domain::{RedirectHop, ResponseInfo};
+use reqwest::Client;
+use reqwest::header::LOCATION;
+use std::time::Instant;
+use url::Url;
+
+const MAX_REDIRECTS: usize = 10;
+
+pub(super) struct HttpResponse {
+    /// URL the body was fetched from, after following redirects
+    pub(super) url: Url,
+    pub(super) body: String,
+    pub(super) redirects: Vec<RedirectHop>,
+    pub(super) info: ResponseInfo,
+}
+
+/// Builds a client that doesn't follow redirects on its own, so that every hop
+/// can be recorded by [`get_following_redirects`].
+pub(super) fn build_client() -> anyhow::Result<Client> {
+    let client = Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()?;
+
+    Ok(client)
+}
+
+pub(super) async fn get_following_redirects(
+    client: &Client,
+    url: &str,
+) -> anyhow::Result<HttpResponse> {
+    let mut current = Url::parse(url)?;
+    let mut redirects = Vec::new();
+
+    loop {
+        let start = Instant::now();
+        let response = client.get(current.clone()).send().await?;
+        let ttfb = start.elapsed();
+        let status = response.status();
+
+        let location = status
+            .is_redirection()
+            .then(|| response.headers().get(LOCATION))
+            .flatten()
+            .and_then(|l| l.to_str().ok())
+            .and_then(|l| current.join(l).ok());
+
+        let Some(location) = location else {
+            let headers = response
+                .headers()
+                .iter()
+                .map(|(name, value)| {
+                    (
+                        name.to_string(),
+                        String::from_utf8_lossy(value.as_bytes()).to_string(),
+                    )
+                })
+                .collect();
+            let body = response.text().await?;
+            let info = ResponseInfo {
+                status: status.as_u16(),
+                headers,
+                ttfb,
+                total: start.elapsed(),
+                body_size: body.len(),
+            };
+
+            return Ok(HttpResponse {
+                url: current,
+                body,
+                redirects,
+                info,
+            });
+        };
+
+        if redirects.len() >= MAX_REDIRECTS {
+            anyhow::bail!("too many redirects (more than {})", MAX_REDIRECTS);
+        }
+
+        redirects.push(RedirectHop {
+            url: current.to_string(),
+            status: status.as_u16(),
+            location: location.to_string(),
+            client: None,
+        });
+        current = location;
+    }
+}
@@ -0,0 +1,169 @@
+use super::text::{clean_text, element_text};
+use crate::domain::{PageDetails, TwitterCard};
+use scraper::{Html, Selector};
+use url::Url;
+
+pub(super) fn extract_details(url: &str, base_url: &Url, document: &Html) -> PageDetails {
+    let title = meta_content(document, r#"meta[property="og:title"]"#).or_else(|| {
+        first_match(document, "title")
+            .map(|element| element_text(&element))
+            .filter(|s| !s.is_empty())
+    });
+
+    let description = meta_content(document, r#"meta[property="og:description"]"#)
+        .or_else(|| meta_content(document, r#"meta[name="description"]"#));
+
+    let twitter = TwitterCard {
+        card: twitter_content(document, "twitter:card"),
+        site: twitter_content(document, "twitter:site"),
+        creator: twitter_content(document, "twitter:creator"),
+        title: twitter_content(document, "twitter:title"),
+        description: twitter_content(document, "twitter:description"),
+        image: twitter_content(document, "twitter:image"),
+    };
+
+    PageDetails {
+        url: url.to_string(),
+        title,
+        description,
+        canonical_url: link_href(document, base_url, r#"link[rel="canonical"]"#),
+        language: first_match(document, "html[lang]")
+            .and_then(|element| element.value().attr("lang"))
+            .map(clean_text),
+        robots: meta_content(document, r#"meta[name="robots"]"#),
+        image: meta_content(document, r#"meta[property="og:image"]"#),
+        og_type: meta_content(document, r#"meta[property="og:type"]"#),
+        site_name: meta_content(document, r#"meta[property="og:site_name"]"#),
+        favicon: link_href(document, base_url, r#"link[rel~="icon"]"#),
+        twitter,
+        json_ld: json_ld_blocks(document),
+        client_redirect: None,
+        redirects: Vec::new(),
+    }
+}
+
+fn first_match<'a>(document: &'a Html, selector: &str) -> Option<scraper::ElementRef<'a>> {
+    let selector = Selector::parse(selector).ok()?;
+    document.select(&selector).next()
+}
+
+fn meta_content(document: &Html, selector: &str) -> Option<String> {
+    first_match(document, selector)
+        .and_then(|element| element.value().attr("content"))
+        .map(clean_text)
+        .filter(|s| !s.is_empty())
+}
+
+// twitter cards are specified via "name", but "property" is common in the wild
+fn twitter_content(document: &Html, name: &str) -> Option<String> {
+    meta_content(document, &format!(r#"meta[name="{}"]"#, name))
+        .or_else(|| meta_content(document, &format!(r#"meta[property="{}"]"#, name)))
+}
+
+fn link_href(document: &Html, base_url: &Url, selector: &str) -> Option<String> {
+    let href = first_match(document, selector)?.value().attr("href")?;
+    base_url.join(href.trim()).ok().map(|u| u.to_string())
+}
+
+// blocks that aren't valid JSON are kept as raw strings so they can still be
+// inspected
+fn json_ld_blocks(document: &Html) -> Vec<serde_json::Value> {
+    let Ok(selector) = Selector::parse(r#"script[type="application/ld+json"]"#) else {
+        return Vec::new();
+    };
+
+    document
+        .select(&selector)
+        .map(|element| element.text().collect::<String>())
+        .filter(|text| !text.trim().is_empty())
+        .map(|text| {
+            serde_json::from_str(&text)
+                .unwrap_or_else(|_| serde_json::Value::String(text.trim().to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn details(head: &str) -> PageDetails {
+        let url = "https://example.com/docs/page";
+        let html = format!(
+            r#"<html lang=" en "><head>{}</head><body></body></html>"#,
+            head
+        );
+        extract_details(url, &Url::parse(url).unwrap(), &Html::parse_document(&html))
+    }
+
+    #[test]
+    fn open_graph_is_preferred_over_standard_tags() {
+        let details = details(
+            r#"<title>Page</title><meta name="description" content="Standard">
+               <meta property="og:title" content="OG page">
+               <meta property="og:description" content=" OG description ">"#,
+        );
+
+        assert_eq!(details.title.as_deref(), Some("OG page"));
+        assert_eq!(details.description.as_deref(), Some("OG description"));
+    }
+
+    #[test]
+    fn standard_tags_are_used_without_open_graph() {
+        let details = details(
+            r#"<title> Page </title><meta name="description" content="Standard">
+               <meta property="og:description" content=" ">"#,
+        );
+
+        assert_eq!(details.title.as_deref(), Some("Page"));
+        assert_eq!(details.description.as_deref(), Some("Standard"));
+        assert_eq!(details.language.as_deref(), Some("en"));
+    }
+
+    #[test]
+    fn link_hrefs_are_resolved_against_the_page() {
+        let details = details(
+            r#"<link rel="canonical" href="/docs/canonical">
+               <link rel="shortcut icon" href="favicon.ico">"#,
+        );
+
+        assert_eq!(
+            details.canonical_url.as_deref(),
+            Some("https://example.com/docs/canonical")
+        );
+        assert_eq!(
+            details.favicon.as_deref(),
+            Some("https://example.com/docs/favicon.ico")
+        );
+    }
+
+    #[test]
+    fn twitter_cards_are_read_from_name_or_property() {
+        let details = details(
+            r#"<meta name="twitter:card" content="summary">
+               <meta property="twitter:site" content="@example">"#,
+        );
+
+        assert_eq!(details.twitter.card.as_deref(), Some("summary"));
+        assert_eq!(details.twitter.site.as_deref(), Some("@example"));
+        assert!(details.twitter.creator.is_none());
+        assert!(TwitterCard::default().is_empty());
+    }
+
+    #[test]
+    fn invalid_json_ld_is_kept_as_a_string() {
+        let details = details(
+            r#"<script type="application/ld+json">{"@type": "Article"}</script>
+               <script type="application/ld+json"> {broken </script>
+               <script type="application/ld+json"> </script>"#,
+        );
+
+        assert_eq!(
+            details.json_ld,
+            [
+                serde_json::json!({"@type": "Article"}),
+                serde_json::Value::String("{broken".to_string())
+            ]
+        );
+    }
+}
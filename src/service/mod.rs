@@ -1,3 +1,14 @@
+mod classify;
+mod context;
 mod fetch;
+mod http;
+mod metadata;
+mod normalize;
+mod reader;
+mod redirect;
+mod source;
+mod text;
 
 pub use fetch::*;
+pub use normalize::*;
+pub use source::*;
@@ -0,0 +1,221 @@
+use clap::ValueEnum;
+use url::{Url, form_urlencoded};
+
+const TRACKING_PARAMS: [&str; 12] = [
+    "fbclid", "gclid", "dclid", "gbraid", "wbraid", "msclkid", "yclid", "igshid", "mc_cid",
+    "mc_eid", "_hsenc", "_hsmi",
+];
+const TRACKING_PARAM_PREFIXES: [&str; 2] = ["utm_", "pk_"];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum TrailingSlash {
+    #[default]
+    Keep,
+    Strip,
+    Add,
+}
+
+#[derive(Clone, Debug)]
+pub struct NormalizeOptions {
+    pub strip_fragments: bool,
+    pub strip_tracking_params: bool,
+    pub trailing_slash: TrailingSlash,
+}
+
+impl Default for NormalizeOptions {
+    fn default() -> Self {
+        Self {
+            strip_fragments: true,
+            strip_tracking_params: true,
+            trailing_slash: TrailingSlash::Keep,
+        }
+    }
+}
+
+/// Returns the canonical form of a URL, used to decide whether two links point
+/// to the same resource.
+///
+/// Hosts are lowercased and default ports are dropped by `Url` itself; the rest
+/// of the steps depend on `options`.
+pub fn normalize_url(url: &Url, options: &NormalizeOptions) -> Url {
+    let mut normalized = url.clone();
+
+    if options.strip_fragments {
+        normalized.set_fragment(None);
+    }
+
+    // the params kept are left as written; decoding and re-encoding them would
+    // change eg. "%20" to "+", or "flag" to "flag="
+    if options.strip_tracking_params
+        && let Some(query) = normalized.query()
+    {
+        let kept = query
+            .split('&')
+            .filter(|param| {
+                !form_urlencoded::parse(param.as_bytes())
+                    .next()
+                    .is_some_and(|(key, _)| is_tracking_param(&key))
+            })
+            .collect::<Vec<_>>()
+            .join("&");
+
+        normalized.set_query((!kept.is_empty()).then_some(kept.as_str()));
+    }
+
+    let path = normalized.path().to_string();
+    match options.trailing_slash {
+        TrailingSlash::Keep => {}
+        TrailingSlash::Strip => {
+            if path.len() > 1 && path.ends_with('/') {
+                normalized.set_path(path.trim_end_matches('/'));
+            }
+        }
+        TrailingSlash::Add => {
+            if !path.ends_with('/') {
+                normalized.set_path(&format!("{}/", path));
+            }
+        }
+    }
+
+    normalized
+}
+
+fn is_tracking_param(key: &str) -> bool {
+    let key = key.to_lowercase();
+    TRACKING_PARAMS.contains(&key.as_str())
+        || TRACKING_PARAM_PREFIXES
+            .iter()
+            .any(|prefix| key.starts_with(prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalize(url: &str, options: &NormalizeOptions) -> String {
+        normalize_url(&Url::parse(url).unwrap(), options).to_string()
+    }
+
+    fn with_trailing_slash(trailing_slash: TrailingSlash) -> NormalizeOptions {
+        NormalizeOptions {
+            trailing_slash,
+            ..NormalizeOptions::default()
+        }
+    }
+
+    #[test]
+    fn fragments_are_stripped_unless_disabled() {
+        let options = NormalizeOptions::default();
+        assert_eq!(
+            normalize("https://example.com/a#intro", &options),
+            "https://example.com/a"
+        );
+
+        let options = NormalizeOptions {
+            strip_fragments: false,
+            ..NormalizeOptions::default()
+        };
+        assert_eq!(
+            normalize("https://example.com/a#intro", &options),
+            "https://example.com/a#intro"
+        );
+    }
+
+    #[test]
+    fn hosts_are_lowercased_and_default_ports_dropped() {
+        assert_eq!(
+            normalize("HTTPS://Example.COM:443/Path", &NormalizeOptions::default()),
+            "https://example.com/Path"
+        );
+    }
+
+    #[test]
+    fn tracking_params_are_stripped() {
+        let options = NormalizeOptions::default();
+        assert_eq!(
+            normalize("https://example.com/?utm_source=x&id=1&fbclid=y", &options),
+            "https://example.com/?id=1"
+        );
+        assert_eq!(
+            normalize("https://example.com/?UTM_Medium=x&gclid=y", &options),
+            "https://example.com/"
+        );
+        assert_eq!(
+            normalize("https://example.com/?utm_source=x&", &options),
+            "https://example.com/"
+        );
+    }
+
+    #[test]
+    fn kept_query_params_are_left_as_written() {
+        let options = NormalizeOptions::default();
+        assert_eq!(
+            normalize("https://example.com/?q=a%20b&flag&utm_source=x", &options),
+            "https://example.com/?q=a%20b&flag"
+        );
+        assert_eq!(
+            normalize("https://example.com/?q=a+b", &options),
+            "https://example.com/?q=a+b"
+        );
+    }
+
+    #[test]
+    fn tracking_params_are_kept_if_disabled() {
+        let options = NormalizeOptions {
+            strip_tracking_params: false,
+            ..NormalizeOptions::default()
+        };
+        assert_eq!(
+            normalize("https://example.com/?utm_source=x", &options),
+            "https://example.com/?utm_source=x"
+        );
+    }
+
+    #[test]
+    fn trailing_slashes_are_stripped() {
+        let options = with_trailing_slash(TrailingSlash::Strip);
+        assert_eq!(
+            normalize("https://example.com/docs/", &options),
+            "https://example.com/docs"
+        );
+        assert_eq!(
+            normalize("https://example.com/docs//?page=2", &options),
+            "https://example.com/docs?page=2"
+        );
+        // the root path can't be empty
+        assert_eq!(
+            normalize("https://example.com/", &options),
+            "https://example.com/"
+        );
+    }
+
+    #[test]
+    fn trailing_slashes_are_added() {
+        let options = with_trailing_slash(TrailingSlash::Add);
+        assert_eq!(
+            normalize("https://example.com/docs", &options),
+            "https://example.com/docs/"
+        );
+        assert_eq!(
+            normalize("https://example.com/docs/#top", &options),
+            "https://example.com/docs/"
+        );
+        assert_eq!(
+            normalize("https://example.com", &options),
+            "https://example.com/"
+        );
+    }
+
+    #[test]
+    fn trailing_slashes_are_kept_by_default() {
+        let options = NormalizeOptions::default();
+        assert_eq!(
+            normalize("https://example.com/docs/", &options),
+            "https://example.com/docs/"
+        );
+        assert_eq!(
+            normalize("https://example.com/docs", &options),
+            "https://example.com/docs"
+        );
+    }
+}
@@ -0,0 +1,324 @@
+use super::normalize::{NormalizeOptions, normalize_url};
+use super::text::link_text;
+use crate::domain::{Reader, ReaderBlock, ReaderBlockKind, ReaderSpan};
+use scraper::{ElementRef, Html, Node, Selector};
+use url::Url;
+
+// tried in order; the first one present is considered to hold the main content
+const CONTENT_SELECTORS: [&str; 4] = ["article", "main", r#"[role="main"]"#, "body"];
+
+const SKIPPED_ELEMENTS: [&str; 14] = [
+    "script", "style", "noscript", "template", "nav", "header", "footer", "aside", "form",
+    "button", "select", "svg", "iframe", "head",
+];
+
+/// Extracts the readable content of a page: headings, paragraphs, list items,
+/// quotes and preformatted blocks, with links kept inline and numbered.
+///
+/// Only links that would be listed for the page (ie, https ones) get a number.
+pub(super) fn extract_reader(
+    base_url: &Url,
+    document: &Html,
+    options: &NormalizeOptions,
+) -> Reader {
+    let root = CONTENT_SELECTORS
+        .iter()
+        .filter_map(|s| Selector::parse(s).ok())
+        .find_map(|selector| document.select(&selector).next())
+        .unwrap_or_else(|| document.root_element());
+
+    let mut builder = ReaderBuilder {
+        base_url,
+        options,
+        reader: Reader::default(),
+        current: None,
+    };
+    builder.walk(root);
+    builder.end_block();
+
+    builder.reader
+}
+
+struct ReaderBuilder<'a> {
+    base_url: &'a Url,
+    options: &'a NormalizeOptions,
+    reader: Reader,
+    current: Option<ReaderBlock>,
+}
+
+impl ReaderBuilder<'_> {
+    fn walk(&mut self, element: ElementRef) {
+        for child in element.children() {
+            match child.value() {
+                Node::Text(text) => self.push_text(text),
+                Node::Element(_) => {
+                    let Some(child) = ElementRef::wrap(child) else {
+                        continue;
+                    };
+                    let name = child.value().name();
+                    if SKIPPED_ELEMENTS.contains(&name) {
+                        continue;
+                    }
+
+                    match block_kind(name) {
+                        Some(ReaderBlockKind::Preformatted) => {
+                            self.end_block();
+                            let text = child.text().collect::<String>();
+                            if !text.trim().is_empty() {
+                                self.reader.blocks.push(ReaderBlock {
+                                    kind: ReaderBlockKind::Preformatted,
+                                    spans: vec![ReaderSpan::Text(
+                                        text.trim_matches('\n').to_string(),
+                                    )],
+                                });
+                            }
+                        }
+                        Some(kind) => {
+                            self.end_block();
+                            self.current = Some(ReaderBlock {
+                                kind,
+                                spans: Vec::new(),
+                            });
+                            self.walk(child);
+                            self.end_block();
+                        }
+                        None if name == "a" => self.push_link(child),
+                        None if name == "br" => self.push_text(" "),
+                        None if is_container(name) => {
+                            self.end_block();
+                            self.walk(child);
+                            self.end_block();
+                        }
+                        None => self.walk(child),
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // text outside of any block (eg, directly inside a div) starts a paragraph
+    fn push_text(&mut self, text: &str) {
+        if self.current.is_none() && text.trim().is_empty() {
+            return;
+        }
+
+        let mut collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if text.starts_with(char::is_whitespace) {
+            collapsed.insert(0, ' ');
+        }
+        if text.ends_with(char::is_whitespace) && !collapsed.ends_with(' ') {
+            collapsed.push(' ');
+        }
+
+        let block = self.current.get_or_insert_with(|| ReaderBlock {
+            kind: ReaderBlockKind::Paragraph,
+            spans: Vec::new(),
+        });
+        match block.spans.last_mut() {
+            Some(ReaderSpan::Text(last)) => {
+                if last.ends_with(' ') {
+                    last.push_str(collapsed.trim_start());
+                } else {
+                    last.push_str(&collapsed);
+                }
+            }
+            _ => block.spans.push(ReaderSpan::Text(collapsed)),
+        }
+    }
+
+    fn push_link(&mut self, element: ElementRef) {
+        let text = link_text(&element).unwrap_or_default();
+        let url = element
+            .value()
+            .attr("href")
+            .and_then(|href| self.base_url.join(href).ok())
+            .filter(|url| url.scheme() == "https")
+            .map(|url| normalize_url(&url, self.options).to_string());
+
+        let Some(url) = url else {
+            self.push_text(&text);
+            return;
+        };
+
+        let number = match self.reader.links.iter().position(|l| l == &url) {
+            Some(index) => index + 1,
+            None => {
+                self.reader.links.push(url);
+                self.reader.links.len()
+            }
+        };
+
+        self.current
+            .get_or_insert_with(|| ReaderBlock {
+                kind: ReaderBlockKind::Paragraph,
+                spans: Vec::new(),
+            })
+            .spans
+            .push(ReaderSpan::Link { text, number });
+    }
+
+    fn end_block(&mut self) {
+        let Some(mut block) = self.current.take() else {
+            return;
+        };
+
+        if let Some(ReaderSpan::Text(first)) = block.spans.first_mut() {
+            *first = first.trim_start().to_string();
+        }
+        if let Some(ReaderSpan::Text(last)) = block.spans.last_mut() {
+            *last = last.trim_end().to_string();
+        }
+        block.spans.retain(|span| match span {
+            ReaderSpan::Text(text) => !text.is_empty(),
+            ReaderSpan::Link { .. } => true,
+        });
+
+        if !block.spans.is_empty() {
+            self.reader.blocks.push(block);
+        }
+    }
+}
+
+fn block_kind(name: &str) -> Option<ReaderBlockKind> {
+    let kind = match name {
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+            ReaderBlockKind::Heading(name[1..].parse().unwrap_or(1))
+        }
+        "p" | "dt" | "dd" | "figcaption" | "caption" | "summary" => ReaderBlockKind::Paragraph,
+        "li" => ReaderBlockKind::ListItem,
+        "blockquote" => ReaderBlockKind::Quote,
+        "pre" => ReaderBlockKind::Preformatted,
+        _ => return None,
+    };
+
+    Some(kind)
+}
+
+// elements that separate blocks of text, without being one themselves
+fn is_container(name: &str) -> bool {
+    matches!(
+        name,
+        "div"
+            | "section"
+            | "article"
+            | "main"
+            | "ul"
+            | "ol"
+            | "dl"
+            | "table"
+            | "tr"
+            | "td"
+            | "th"
+            | "figure"
+            | "details"
+            | "hr"
+            | "body"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reader(body: &str) -> Reader {
+        let base_url = Url::parse("https://example.com/docs/").unwrap();
+        let document = Html::parse_document(&format!("<html><body>{}</body></html>", body));
+        extract_reader(&base_url, &document, &NormalizeOptions::default())
+    }
+
+    // one line per block, with links written as "text[number]"
+    fn blocks(reader: &Reader) -> Vec<String> {
+        reader
+            .blocks
+            .iter()
+            .map(|block| {
+                let text: String = block
+                    .spans
+                    .iter()
+                    .map(|span| match span {
+                        ReaderSpan::Text(text) => text.clone(),
+                        ReaderSpan::Link { text, number } => format!("{}[{}]", text, number),
+                    })
+                    .collect();
+                format!("{:?}: {}", block.kind, text)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn blocks_keep_their_kind() {
+        let reader = reader(
+            "<h2>Title</h2><p>Intro</p><ul><li>One</li><li>Two</li></ul>\
+             <blockquote>Quoted</blockquote><pre>\nfn main() {\n    run();\n}\n</pre>",
+        );
+
+        assert_eq!(
+            blocks(&reader),
+            [
+                "Heading(2): Title",
+                "Paragraph: Intro",
+                "ListItem: One",
+                "ListItem: Two",
+                "Quote: Quoted",
+                "Preformatted: fn main() {\n    run();\n}",
+            ]
+        );
+    }
+
+    #[test]
+    fn inline_whitespace_is_collapsed_between_elements() {
+        let reader = reader("<p>\n  Hello <b>bold</b>\n  <i>world</i>,<br>again  </p>");
+
+        assert_eq!(blocks(&reader), ["Paragraph: Hello bold world, again"]);
+    }
+
+    #[test]
+    fn loose_text_in_containers_becomes_a_paragraph() {
+        let reader = reader("<div>Loose <em>text</em><div>Nested</div>After</div>");
+
+        assert_eq!(
+            blocks(&reader),
+            [
+                "Paragraph: Loose text",
+                "Paragraph: Nested",
+                "Paragraph: After"
+            ]
+        );
+    }
+
+    #[test]
+    fn main_content_is_preferred_and_chrome_skipped() {
+        let reader = reader(
+            "<nav><a href='/'>Home</a></nav><p>Outside</p>\
+             <main><p>Inside</p><script>var a;</script><aside>Related</aside></main>",
+        );
+
+        assert_eq!(blocks(&reader), ["Paragraph: Inside"]);
+        assert!(reader.links.is_empty());
+    }
+
+    #[test]
+    fn links_are_numbered_in_order_of_first_appearance() {
+        let reader = reader(
+            "<p>See <a href='b'>B</a> and <a href='https://example.org/a'>A</a>.</p>\
+             <p>Back to <a href='b#top'>B again</a>, <a href='http://example.org/'>plain</a> \
+             or <a href='mailto:a@example.com'>mail</a>.</p>",
+        );
+
+        assert_eq!(
+            blocks(&reader),
+            [
+                "Paragraph: See B[1] and A[2].",
+                "Paragraph: Back to B again[1], plain or mail.",
+            ]
+        );
+        assert_eq!(
+            reader.links,
+            ["https://example.com/docs/b", "https://example.org/a"]
+        );
+        assert_eq!(reader.link(2), Some("https://example.org/a"));
+        assert_eq!(reader.link(0), None);
+        assert_eq!(reader.link(3), None);
+    }
+}
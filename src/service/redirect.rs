@@ -0,0 +1,179 @@
+use crate::domain::{ClientRedirect, ClientRedirectKind};
+use regex::Regex;
+use scraper::{Html, Selector};
+use std::sync::LazyLock;
+use url::Url;
+
+// "location" has to be a property of window/document/top/self, or start a
+// statement, so that eg. "item.location = ..." or "geolocation = ..." aren't
+// mistaken for redirects
+#[allow(clippy::unwrap_used)]
+static SCRIPT_REDIRECT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?m)(?:\b(?:window|document|top|self)\.|(?:^|[;{}])\s*)location(?:(?:\.href)?\s*=\s*["']([^"']+)["']|\.(?:replace|assign)\(\s*["']([^"']+)["']\s*\))"#,
+    )
+    .unwrap()
+});
+
+/// Detects redirects performed by the page itself, via a meta refresh tag or
+/// (common patterns of) inline scripts.
+pub(super) fn detect_client_redirect(base_url: &Url, document: &Html) -> Option<ClientRedirect> {
+    meta_refresh_target(document)
+        .map(|target| (target, ClientRedirectKind::MetaRefresh))
+        .or_else(|| script_redirect_target(document).map(|t| (t, ClientRedirectKind::Script)))
+        .and_then(|(target, kind)| {
+            let url = base_url.join(&target).ok()?;
+            matches!(url.scheme(), "http" | "https").then(|| ClientRedirect {
+                url: url.to_string(),
+                kind,
+            })
+        })
+}
+
+// content looks like "5; url=https://example.com", with the "url=" part and the
+// quotes around the URL being optional
+fn meta_refresh_target(document: &Html) -> Option<String> {
+    let selector = Selector::parse("meta[http-equiv]").ok()?;
+    let content = document
+        .select(&selector)
+        .find(|element| {
+            element
+                .value()
+                .attr("http-equiv")
+                .is_some_and(|v| v.trim().eq_ignore_ascii_case("refresh"))
+        })?
+        .value()
+        .attr("content")?;
+
+    let (_, target) = content.split_once([';', ','])?;
+    let target = target.trim();
+    let target = match target.get(..3) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("url") => {
+            target[3..].trim_start().strip_prefix('=')?.trim()
+        }
+        _ => target,
+    };
+    let target = target.trim_matches(['\'', '"']).trim();
+
+    (!target.is_empty()).then(|| target.to_string())
+}
+
+fn script_redirect_target(document: &Html) -> Option<String> {
+    let selector = Selector::parse("script:not([src])").ok()?;
+    document.select(&selector).find_map(|element| {
+        let script = element.text().collect::<String>();
+        SCRIPT_REDIRECT_RE
+            .captures(&script)
+            .and_then(|c| c.get(1).or_else(|| c.get(2)))
+            .map(|m| m.as_str().to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn script_target(script: &str) -> Option<String> {
+        let html = format!("<html><head><script>{}</script></head></html>", script);
+        script_redirect_target(&Html::parse_document(&html))
+    }
+
+    fn meta_target(content: &str) -> Option<String> {
+        let html = format!(
+            r#"<html><head><meta http-equiv="Refresh" content="{}"></head></html>"#,
+            content
+        );
+        meta_refresh_target(&Html::parse_document(&html))
+    }
+
+    #[test]
+    fn script_redirects_via_window_properties_are_detected() {
+        assert_eq!(
+            script_target(r#"window.location.href = "/next";"#).as_deref(),
+            Some("/next")
+        );
+        assert_eq!(
+            script_target("document.location='/doc'").as_deref(),
+            Some("/doc")
+        );
+        assert_eq!(
+            script_target(r#"top.location.replace("/top")"#).as_deref(),
+            Some("/top")
+        );
+        assert_eq!(
+            script_target(r#"self.location.assign( '/self' )"#).as_deref(),
+            Some("/self")
+        );
+    }
+
+    #[test]
+    fn script_redirects_via_bare_location_at_statement_start_are_detected() {
+        assert_eq!(
+            script_target(r#"location = "/bare";"#).as_deref(),
+            Some("/bare")
+        );
+        assert_eq!(
+            script_target("var a = 1;\n  location.href = '/line'").as_deref(),
+            Some("/line")
+        );
+        assert_eq!(
+            script_target(r#"if (a) { location.replace("/block") }"#).as_deref(),
+            Some("/block")
+        );
+    }
+
+    #[test]
+    fn other_location_assignments_are_not_script_redirects() {
+        assert_eq!(script_target(r#"var geolocation = "none";"#), None);
+        assert_eq!(script_target(r#"item.location = "Paris";"#), None);
+        assert_eq!(script_target(r#"mywindow.location = "/x";"#), None);
+        assert_eq!(script_target(r#"item.location.replace("/x");"#), None);
+        assert_eq!(script_target(r#"if (location == "/x") {}"#), None);
+    }
+
+    #[test]
+    fn meta_refresh_targets_are_parsed() {
+        assert_eq!(
+            meta_target("0; url=https://example.com/").as_deref(),
+            Some("https://example.com/")
+        );
+        assert_eq!(meta_target("5;URL = '/quoted'").as_deref(), Some("/quoted"));
+        assert_eq!(
+            meta_target("3, /no-url-prefix").as_deref(),
+            Some("/no-url-prefix")
+        );
+        assert_eq!(
+            meta_target("0; url=&quot;/double&quot;").as_deref(),
+            Some("/double")
+        );
+    }
+
+    #[test]
+    fn meta_refreshes_without_a_target_are_ignored() {
+        assert_eq!(meta_target("30"), None);
+        assert_eq!(meta_target("0; url="), None);
+        assert_eq!(meta_target("0; url=''"), None);
+    }
+
+    #[test]
+    fn client_redirects_are_resolved_against_the_page() {
+        let base_url = Url::parse("https://example.com/docs/page").unwrap();
+        let document = Html::parse_document(
+            r#"<html><head><meta http-equiv="refresh" content="0; url=next"></head></html>"#,
+        );
+        let redirect = detect_client_redirect(&base_url, &document).unwrap();
+
+        assert_eq!(redirect.url, "https://example.com/docs/next");
+        assert_eq!(redirect.kind, ClientRedirectKind::MetaRefresh);
+    }
+
+    #[test]
+    fn client_redirects_to_other_schemes_are_ignored() {
+        let base_url = Url::parse("https://example.com/").unwrap();
+        let document = Html::parse_document(
+            r#"<html><head><script>location.href = "javascript:void(0)";</script></head></html>"#,
+        );
+
+        assert!(detect_client_redirect(&base_url, &document).is_none());
+    }
+}
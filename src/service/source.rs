@@ -0,0 +1,71 @@
+use super::normalize::{NormalizeOptions, normalize_url};
+use crate::domain::Page;
+use regex::Regex;
+use scraper::{Html, Selector};
+use std::sync::LazyLock;
+use url::Url;
+
+#[allow(clippy::unwrap_used)]
+static ANCHOR_TAG_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?is)<a\b[^>]*>").unwrap());
+
+/// Returns the (0-indexed) lines of the page's source covered by the anchor
+/// tags that point to `url`, a normalized URL as listed for the page.
+pub fn anchor_source_lines(page: &Page, url: &str, options: &NormalizeOptions) -> Vec<usize> {
+    let Ok(base_url) = Url::parse(page.details.final_url()) else {
+        return Vec::new();
+    };
+    #[allow(clippy::unwrap_used)]
+    let selector = Selector::parse("a").unwrap();
+
+    let mut lines = Vec::new();
+    let mut line = 0;
+    let mut offset = 0;
+    for tag in ANCHOR_TAG_RE.find_iter(&page.source) {
+        line += page.source[offset..tag.start()].matches('\n').count();
+        offset = tag.start();
+
+        // parsing the tag on its own takes care of quoting and entities in the
+        // href, the same way as when the page was parsed
+        let fragment = Html::parse_fragment(tag.as_str());
+        let points_to_url = fragment
+            .select(&selector)
+            .next()
+            .and_then(|a| a.value().attr("href"))
+            .and_then(|href| base_url.join(href).ok())
+            .is_some_and(|link| normalize_url(&link, options).as_str() == url);
+
+        if points_to_url {
+            let span = tag.as_str().matches('\n').count();
+            lines.extend(line..=line + span);
+        }
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(source: &str, url: &str) -> Vec<usize> {
+        let mut page = Page::with_links("https://example.com/docs/", vec![]);
+        page.source = source.to_string();
+        anchor_source_lines(&page, url, &NormalizeOptions::default())
+    }
+
+    #[test]
+    fn lines_of_anchors_pointing_to_the_url_are_found() {
+        let source = "<p>\n<a href=\"a\">A</a>\n<a href='/b'>B</a> <A HREF=\"a#top\">A</A>\n</p>";
+
+        assert_eq!(lines(source, "https://example.com/docs/a"), [1, 2]);
+        assert_eq!(lines(source, "https://example.com/b"), [2]);
+        assert!(lines(source, "https://example.com/c").is_empty());
+    }
+
+    #[test]
+    fn anchors_spanning_lines_cover_all_of_them() {
+        let source = "<p>\n<a class=\"x\"\n   href=\"/a?x=1&amp;y=2\"\n>A</a>";
+
+        assert_eq!(lines(source, "https://example.com/a?x=1&y=2"), [1, 2, 3]);
+    }
+}
@@ -0,0 +1,117 @@
+use scraper::ElementRef;
+
+/// Returns the human readable text of an element: nested markup is stripped,
+/// entities are decoded and whitespace is collapsed.
+pub(super) fn element_text(element: &ElementRef) -> String {
+    clean_text(&element.text().collect::<String>())
+}
+
+/// Cleans up text extracted from a document (text nodes or attribute values).
+///
+/// The parser has already decoded entities and skipped markup, so anything
+/// that looks like a tag (eg. "&lt;canvas&gt;" in a title) was meant to be
+/// shown as is.
+pub(super) fn clean_text(raw: &str) -> String {
+    collapse_whitespace(raw)
+}
+
+// falls back to attributes commonly used to label links without text, like
+// image links
+pub(super) fn link_text(element: &ElementRef) -> Option<String> {
+    let text = element_text(element);
+    if !text.is_empty() {
+        return Some(text);
+    }
+
+    let img_alt = element
+        .children()
+        .filter_map(ElementRef::wrap)
+        .find(|child| child.value().name() == "img")
+        .and_then(|img| img.value().attr("alt"));
+
+    [
+        element.value().attr("aria-label"),
+        element.value().attr("title"),
+        img_alt,
+    ]
+    .into_iter()
+    .flatten()
+    .map(clean_text)
+    .find(|s| !s.is_empty())
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scraper::{Html, Selector};
+
+    fn first_element_text(html: &str, selector: &str) -> String {
+        let document = Html::parse_document(html);
+        let selector = Selector::parse(selector).unwrap();
+        element_text(&document.select(&selector).next().unwrap())
+    }
+
+    #[test]
+    fn escaped_markup_is_kept_as_text() {
+        assert_eq!(
+            first_element_text("<title>The &lt;canvas&gt; element</title>", "title"),
+            "The <canvas> element"
+        );
+        assert_eq!(
+            first_element_text("<p>Use the &lt;div&gt; tag</p>", "p"),
+            "Use the <div> tag"
+        );
+        assert_eq!(
+            clean_text("<canvas>: The Graphics Canvas element"),
+            "<canvas>: The Graphics Canvas element"
+        );
+    }
+
+    #[test]
+    fn nested_markup_is_stripped_and_entities_decoded() {
+        assert_eq!(
+            first_element_text("<p>Fish <b>&amp;</b> <i>chips</i></p>", "p"),
+            "Fish & chips"
+        );
+    }
+
+    #[test]
+    fn whitespace_is_collapsed() {
+        assert_eq!(
+            first_element_text("<p>\n  Some\t\ttext,\n  over  lines\n</p>", "p"),
+            "Some text, over lines"
+        );
+        assert_eq!(clean_text("  \n "), "");
+    }
+
+    fn first_link_text(html: &str) -> Option<String> {
+        let document = Html::parse_fragment(html);
+        let selector = Selector::parse("a").unwrap();
+        link_text(&document.select(&selector).next().unwrap())
+    }
+
+    #[test]
+    fn link_text_falls_back_to_labelling_attributes() {
+        assert_eq!(
+            first_link_text(r#"<a href="/" title="Home">  Go <b>home</b> </a>"#).as_deref(),
+            Some("Go home")
+        );
+        assert_eq!(
+            first_link_text(r#"<a href="/" aria-label="Home" title="Start"> </a>"#).as_deref(),
+            Some("Home")
+        );
+        assert_eq!(
+            first_link_text(r#"<a href="/" title=" "><img src="logo.png" alt="Logo"></a>"#)
+                .as_deref(),
+            Some("Logo")
+        );
+        assert_eq!(
+            first_link_text(r#"<a href="/"><img src="logo.png"></a>"#),
+            None
+        );
+    }
+}
@@ -1,11 +1,13 @@
 use super::common::*;
-use super::event::get_event_handling_msg;
+use super::event::PendingKeys;
 use super::handle::handle_command;
 use super::message::Message;
 use super::model::{Model, RunningState};
-use super::update::update;
+use super::prefetch::PrefetchOptions;
+use super::update::{fetch_in_background, update};
 use super::view::view;
-use crate::domain::Page;
+use crate::domain::{LinkOrder, Page, UrlFilter};
+use crate::service::FetchOptions;
 use ratatui::Terminal;
 use ratatui::backend::CrosstermBackend;
 use std::io::Error as IOError;
@@ -15,8 +17,16 @@ use tokio::sync::mpsc::{Receiver, Sender};
 
 const EVENT_POLL_DURATION_MS: u64 = 16;
 
-pub async fn run_tui(page: Page) -> anyhow::Result<()> {
-    let mut tui = AppTui::new(page)?;
+pub struct TuiConfig {
+    pub filter: UrlFilter,
+    pub order: LinkOrder,
+    pub fetch_options: FetchOptions,
+    pub no_follow_rels: Vec<String>,
+    pub prefetch: PrefetchOptions,
+}
+
+pub async fn run_tui(page: Page, config: TuiConfig) -> anyhow::Result<()> {
+    let mut tui = AppTui::new(page, config)?;
     tui.run().await?;
 
     Ok(())
@@ -27,12 +37,14 @@ struct AppTui {
     pub(super) event_tx: Sender<Message>,
     pub(super) event_rx: Receiver<Message>,
     pub(super) model: Model,
+    pub(super) pending_keys: PendingKeys,
 }
 
 impl AppTui {
-    pub fn new(page: Page) -> anyhow::Result<Self> {
+    pub fn new(page: Page, config: TuiConfig) -> anyhow::Result<Self> {
         let terminal = ratatui::try_init()?;
-        let (event_tx, event_rx) = mpsc::channel(10);
+        // room for the results of background fetches arriving together
+        let (event_tx, event_rx) = mpsc::channel(32);
 
         let (width, height) = ratatui::crossterm::terminal::size()?;
 
@@ -40,13 +52,14 @@ impl AppTui {
 
         let debug = std::env::var("URLL_DEBUG").unwrap_or_default().trim() == "1";
 
-        let model = Model::new(page, terminal_dimensions, debug);
+        let model = Model::new(page, config, terminal_dimensions, debug);
 
         Ok(Self {
             terminal,
             event_tx,
             event_rx,
             model,
+            pending_keys: PendingKeys::default(),
         })
     }
 
@@ -57,22 +70,19 @@ impl AppTui {
         self.model.render_counter += 1;
         self.terminal.draw(|f| view(&mut self.model, f))?;
 
+        // later prefetches follow updates
+        if self.model.prefetch.enabled {
+            for cmd in fetch_in_background(&mut self.model) {
+                handle_command(cmd, self.event_tx.clone()).await;
+            }
+        }
+
         loop {
             tokio::select! {
                 Some(message) = self.event_rx.recv() => {
-                    let cmds = update(&mut self.model, message);
-
-                    if self.model.running_state == RunningState::Done {
-                        self.exit()?;
+                    if self.process(message).await? {
                         return Ok(());
                     }
-
-                        self.model.render_counter += 1;
-                        self.terminal.draw(|f| view(&mut self.model, f))?;
-
-                    for cmd in cmds {
-                        handle_command(cmd, self.event_tx.clone()).await;
-                    }
                 }
 
                 Ok(ready) = tokio::task::spawn_blocking(|| ratatui::crossterm::event::poll(Duration::from_millis(EVENT_POLL_DURATION_MS))) => {
@@ -80,11 +90,26 @@ impl AppTui {
                         Ok(true) => {
                             let event = ratatui::crossterm::event::read()?;
                             self.model.event_counter += 1;
-                            if let Some(handling_msg) = get_event_handling_msg(&self.model, event) {
-                                self.event_tx.try_send(handling_msg)?;
+                            // handled right away rather than queued behind
+                            // the results of background fetches, which could
+                            // fill up the channel
+                            for handling_msg in self.pending_keys.get_event_handling_msgs(&self.model, event) {
+                                if self.process(handling_msg).await? {
+                                    return Ok(());
+                                }
+                            }
+                        }
+                        Ok(false) => {
+                            if let Some(handling_msg) = self.pending_keys.expired()
+                                && self.process(handling_msg).await?
+                            {
+                                return Ok(());
+                            }
+                            // keeps the loading indicator moving
+                            if self.model.loading.is_some() {
+                                self.terminal.draw(|f| view(&mut self.model, f))?;
                             }
                         }
-                        Ok(false) => continue,
                         Err(e) => {
                                 return Err(anyhow::anyhow!(e));
                         }
@@ -94,6 +119,26 @@ impl AppTui {
         }
     }
 
+    /// Updates the model with `message`, renders it and runs the resulting
+    /// commands. Returns true once the TUI has exited.
+    async fn process(&mut self, message: Message) -> anyhow::Result<bool> {
+        let cmds = update(&mut self.model, message);
+
+        if self.model.running_state == RunningState::Done {
+            self.exit()?;
+            return Ok(true);
+        }
+
+        self.model.render_counter += 1;
+        self.terminal.draw(|f| view(&mut self.model, f))?;
+
+        for cmd in cmds {
+            handle_command(cmd, self.event_tx.clone()).await;
+        }
+
+        Ok(false)
+    }
+
     fn exit(&mut self) -> Result<(), IOError> {
         ratatui::try_restore()
     }
@@ -0,0 +1,254 @@
+use super::common::Pane;
+use super::message::Message;
+use super::model::parse_url_input;
+use crate::domain::LinkOrder;
+use clap::ValueEnum;
+
+/// Options that can be turned on and off with `:set`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Setting {
+    RawUrls,
+    MillerColumns,
+    Prefetch,
+}
+
+const SETTINGS: [(&str, Setting); 3] = [
+    ("raw", Setting::RawUrls),
+    ("columns", Setting::MillerColumns),
+    ("prefetch", Setting::Prefetch),
+];
+
+const EXPORT_TARGETS: [&str; 3] = ["marks", "history", "history-json"];
+
+// names of commands, along with the arguments they take
+const COMMANDS: [(&str, &str); 18] = [
+    ("open", "<url>"),
+    ("filter", "[spec]"),
+    ("sort", "[order]"),
+    ("export", "<marks|history|history-json>"),
+    ("set", "[no]<raw|columns|prefetch>[!]"),
+    ("refresh", ""),
+    ("bookmark", ""),
+    ("back", ""),
+    ("forward", ""),
+    ("tab", "[number]"),
+    ("tabclose", ""),
+    ("mark-all", ""),
+    ("clear-marks", ""),
+    ("invert-marks", ""),
+    ("crawl", ""),
+    ("history", ""),
+    ("help", ""),
+    ("quit", ""),
+];
+
+/// Parses a command entered on the command line (eg. `sort host`) into the
+/// message it stands for.
+pub(super) fn parse_command(input: &str) -> Result<Message, String> {
+    let input = input.trim();
+    let (name, args) = input
+        .split_once(char::is_whitespace)
+        .map_or((input, ""), |(name, args)| (name, args.trim()));
+
+    let no_args = |message: Message| match args.is_empty() {
+        true => Ok(message),
+        false => Err(format!("{} doesn't take arguments", name)),
+    };
+
+    match name {
+        "open" => parse_url_input(args).map(Message::OpenUrl),
+        "filter" => Ok(Message::ApplyFilter(args.to_string())),
+        "sort" if args.is_empty() => Ok(Message::CycleSortOrder),
+        "sort" => LinkOrder::from_str(args, true)
+            .map(Message::SetSortOrder)
+            .map_err(|_| format!("unknown order \"{}\"", args)),
+        "export" => match args {
+            "marks" => Ok(Message::ExportMarks),
+            "history" => Ok(Message::ExportVisitsAsMarkdown),
+            "history-json" => Ok(Message::ExportVisitsAsJson),
+            _ => Err(format!("usage: export {}", usage("export"))),
+        },
+        "set" => parse_setting(args),
+        "refresh" => no_args(Message::Refresh),
+        "bookmark" => no_args(Message::Bookmark),
+        "back" => no_args(Message::GoBack),
+        "forward" => no_args(Message::GoForward),
+        "tab" if args.is_empty() => Ok(Message::OpenTab),
+        "tab" => match args.parse::<usize>() {
+            Ok(number) if number > 0 => Ok(Message::GoToTab(number - 1)),
+            _ => Err(format!("invalid tab number \"{}\"", args)),
+        },
+        "tabclose" => no_args(Message::CloseTab),
+        "mark-all" => no_args(Message::MarkAllVisible),
+        "clear-marks" => no_args(Message::ClearMarks),
+        "invert-marks" => no_args(Message::InvertMarks),
+        "crawl" => no_args(Message::CrawlMarks),
+        "history" => no_args(Message::GoToPane(Pane::History)),
+        "help" => no_args(Message::GoToPane(Pane::Help)),
+        "quit" | "q" => no_args(Message::QuitImmediately),
+        "" => Err("enter a command".to_string()),
+        _ => Err(format!("unknown command \"{}\"", name)),
+    }
+}
+
+// vim style: "set raw" turns an option on, "set noraw" off, and "set raw!"
+// toggles it
+fn parse_setting(args: &str) -> Result<Message, String> {
+    let (name, value) = match (args.strip_suffix('!'), args.strip_prefix("no")) {
+        (Some(name), _) => (name, None),
+        (None, Some(name)) if setting(name).is_some() => (name, Some(false)),
+        (None, _) => (args, Some(true)),
+    };
+
+    setting(name)
+        .map(|s| Message::Set(s, value))
+        .ok_or_else(|| format!("usage: set {}", usage("set")))
+}
+
+fn setting(name: &str) -> Option<Setting> {
+    SETTINGS.iter().find(|(n, _)| *n == name).map(|(_, s)| *s)
+}
+
+fn usage(name: &str) -> &'static str {
+    COMMANDS
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map_or("", |(_, usage)| usage)
+}
+
+/// Returns the ways to complete the command line's input: command names
+/// while the first word is being typed, then the arguments of the command
+/// (URLs for `open`, which are looked up with `url_completions`).
+pub(super) fn command_completions(
+    input: &str,
+    url_completions: impl FnOnce(&str) -> Vec<String>,
+) -> Vec<String> {
+    let Some((name, arg)) = input.split_once(' ') else {
+        return COMMANDS
+            .iter()
+            .map(|(name, _)| *name)
+            .filter(|name| name.starts_with(input))
+            .map(|name| name.to_string())
+            .collect();
+    };
+
+    let candidates: Vec<String> = match name {
+        "open" => url_completions(arg),
+        "sort" => LinkOrder::value_variants()
+            .iter()
+            .filter_map(|order| order.to_possible_value())
+            .map(|value| value.get_name().to_string())
+            .collect(),
+        "export" => EXPORT_TARGETS.iter().map(|t| t.to_string()).collect(),
+        "set" => SETTINGS
+            .iter()
+            .flat_map(|(name, _)| [name.to_string(), format!("no{}", name)])
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    candidates
+        .into_iter()
+        .filter(|candidate| name == "open" || candidate.starts_with(arg))
+        .map(|candidate| format!("{} {}", name, candidate))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_are_turned_on_off_or_toggled() {
+        assert!(matches!(
+            parse_command("set raw"),
+            Ok(Message::Set(Setting::RawUrls, Some(true)))
+        ));
+        assert!(matches!(
+            parse_command("set noprefetch"),
+            Ok(Message::Set(Setting::Prefetch, Some(false)))
+        ));
+        assert!(matches!(
+            parse_command("set columns!"),
+            Ok(Message::Set(Setting::MillerColumns, None))
+        ));
+    }
+
+    #[test]
+    fn invalid_settings_are_rejected() {
+        for input in ["set", "set no", "set noraw!", "set nope", "set raw!!"] {
+            assert!(parse_command(input).is_err(), "{} was accepted", input);
+        }
+    }
+
+    #[test]
+    fn commands_are_parsed_with_their_arguments() {
+        assert!(matches!(
+            parse_command("  open example.com/docs "),
+            Ok(Message::OpenUrl(url)) if url == "https://example.com/docs"
+        ));
+        assert!(matches!(
+            parse_command("filter --include /docs/"),
+            Ok(Message::ApplyFilter(spec)) if spec == "--include /docs/"
+        ));
+        assert!(matches!(
+            parse_command("sort Host"),
+            Ok(Message::SetSortOrder(LinkOrder::Host))
+        ));
+        assert!(matches!(parse_command("sort"), Ok(Message::CycleSortOrder)));
+        assert!(matches!(
+            parse_command("export history-json"),
+            Ok(Message::ExportVisitsAsJson)
+        ));
+        assert!(matches!(parse_command("tab 2"), Ok(Message::GoToTab(1))));
+        assert!(matches!(parse_command("tab"), Ok(Message::OpenTab)));
+        assert!(matches!(parse_command("q"), Ok(Message::QuitImmediately)));
+    }
+
+    #[test]
+    fn invalid_commands_are_rejected() {
+        for input in [
+            "",
+            "bogus",
+            "open",
+            "open ftp://example.com",
+            "sort random",
+            "export",
+            "tab 0",
+            "tab two",
+            "refresh now",
+        ] {
+            assert!(parse_command(input).is_err(), "{:?} was accepted", input);
+        }
+    }
+
+    #[test]
+    fn command_names_are_completed() {
+        let no_urls = |_: &str| -> Vec<String> { unreachable!() };
+        assert_eq!(command_completions("so", no_urls), vec!["sort"]);
+        assert_eq!(command_completions("t", no_urls), vec!["tab", "tabclose"]);
+        assert!(command_completions("x", no_urls).is_empty());
+    }
+
+    #[test]
+    fn arguments_are_completed() {
+        let no_urls = |_: &str| -> Vec<String> { unreachable!() };
+        assert_eq!(command_completions("sort h", no_urls), vec!["sort host"]);
+        assert_eq!(
+            command_completions("set no", no_urls),
+            vec!["set noraw", "set nocolumns", "set noprefetch"]
+        );
+        assert_eq!(
+            command_completions("export hist", no_urls),
+            vec!["export history", "export history-json"]
+        );
+        assert_eq!(
+            command_completions("open exa", |input| vec![format!(
+                "https://{}mple.com/",
+                input
+            )]),
+            vec!["open https://example.com/"]
+        );
+        assert!(command_completions("refresh ", no_urls).is_empty());
+    }
+}
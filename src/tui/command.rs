@@ -1,8 +1,23 @@
-#[derive(Clone, Debug)]
+use crate::service::FetchOptions;
+use tokio::sync::oneshot;
+
+#[derive(Debug)]
 pub(super) enum Command {
-    GetResults { current: String, chosen: String },
+    GetResults {
+        request_id: u64,
+        url: String,
+        options: FetchOptions,
+        /// aborts the fetch when a value is sent on it
+        cancel: oneshot::Receiver<()>,
+    },
     YankContentToClipboard(String),
     OpenInBrowser(String),
+    WriteToFile {
+        path: String,
+        contents: String,
+        /// adds to the end of the file instead of replacing it
+        append: bool,
+    },
 }
 
 impl std::fmt::Display for Command {
@@ -11,6 +26,7 @@ impl std::fmt::Display for Command {
             Command::GetResults { .. } => write!(f, "get services"),
             Command::YankContentToClipboard(_) => write!(f, "yank url"),
             Command::OpenInBrowser(_) => write!(f, "open url"),
+            Command::WriteToFile { .. } => write!(f, "write to file"),
         }
     }
 }
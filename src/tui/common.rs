@@ -8,14 +8,41 @@ pub const SECONDARY_COLOR: Color = Color::from_u32(0xb8bb26);
 pub const HELP_COLOR: Color = Color::from_u32(0xfabd2f);
 pub const INFO_MESSAGE_COLOR: Color = Color::from_u32(0x83a598);
 pub const ERROR_COLOR: Color = Color::from_u32(0xfb4934);
+pub const FRAGMENT_LINK_COLOR: Color = Color::from_u32(0x928374);
+pub const DOWNLOAD_LINK_COLOR: Color = Color::from_u32(0xd3869b);
+pub const INTERNAL_LINK_COLOR: Color = Color::from_u32(0xb8bb26);
+pub const SUBDOMAIN_LINK_COLOR: Color = Color::from_u32(0x8ec07c);
+pub const EXTERNAL_LINK_COLOR: Color = Color::from_u32(0xfe8019);
+pub const REDIRECT_COLOR: Color = Color::from_u32(0x83a598);
+pub const REL_BADGE_COLOR: Color = Color::from_u32(0xfabd2f);
+pub const REL_BADGE_DIM_COLOR: Color = Color::from_u32(0x7c6f64);
+pub const SOURCE_TAG_COLOR: Color = Color::from_u32(0x83a598);
+pub const SOURCE_ATTRIBUTE_COLOR: Color = Color::from_u32(0xfabd2f);
+pub const SOURCE_VALUE_COLOR: Color = Color::from_u32(0xb8bb26);
+pub const SOURCE_COMMENT_COLOR: Color = Color::from_u32(0x928374);
+pub const SOURCE_RAW_TEXT_COLOR: Color = Color::from_u32(0xd5c4a1);
+pub const SOURCE_PUNCTUATION_COLOR: Color = Color::from_u32(0x7c6f64);
+pub const SOURCE_HIGHLIGHT_BG_COLOR: Color = Color::from_u32(0x504945);
+pub const SEARCH_MATCH_COLOR: Color = Color::from_u32(0xfabd2f);
+pub const BREADCRUMB_COLOR: Color = Color::from_u32(0x928374);
 
 pub const TITLE: &str = " urll ";
 pub const MIN_TERMINAL_WIDTH: u16 = 64;
 pub const MIN_TERMINAL_HEIGHT: u16 = 30;
+pub const HISTORY_MARKDOWN_FILE: &str = "urll-history.md";
+pub const HISTORY_JSON_FILE: &str = "urll-history.json";
+pub const MARKS_FILE: &str = "urll-marks.txt";
+pub const BOOKMARKS_FILE: &str = "urll-bookmarks.txt";
 
 #[derive(PartialEq, Debug, Clone, Copy)]
 pub enum Pane {
     ResultsList,
+    PageDetails,
+    Redirects,
+    Response,
+    Reader,
+    Source,
+    History,
     Help,
 }
 
@@ -23,6 +50,12 @@ impl std::fmt::Display for Pane {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Pane::ResultsList => write!(f, "rl"),
+            Pane::PageDetails => write!(f, "pd"),
+            Pane::Redirects => write!(f, "rd"),
+            Pane::Response => write!(f, "rs"),
+            Pane::Reader => write!(f, "rm"),
+            Pane::Source => write!(f, "src"),
+            Pane::History => write!(f, "hs"),
             Pane::Help => write!(f, "h"),
         }
     }
@@ -2,6 +2,57 @@ use super::common::*;
 use super::message::Message;
 use super::model::*;
 use ratatui::crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers};
+use std::time::{Duration, Instant};
+
+// how long a "g" waits for a "t"/"T" before being taken to mean "go to the top"
+const PENDING_G_TIMEOUT: Duration = Duration::from_millis(1000);
+
+/// Keys that start a sequence (only "g", for "gt"/"gT") and are waiting for the
+/// next one. These are kept out of the model, so that messages arriving in
+/// between (eg. the results of background fetches) can't affect them.
+#[derive(Default)]
+pub(super) struct PendingKeys {
+    g: Option<Instant>,
+}
+
+impl PendingKeys {
+    /// Returns the messages for `event`, holding back "g" in the results list
+    /// while there are tabs to switch to, until it's clear whether it starts
+    /// "gt"/"gT" or means "go to the top".
+    pub(super) fn get_event_handling_msgs(&mut self, model: &Model, event: Event) -> Vec<Message> {
+        let key_code = match &event {
+            Event::Key(key_event) if key_event.kind == KeyEventKind::Press => key_event.code,
+            _ => return get_event_handling_msg(model, event).into_iter().collect(),
+        };
+
+        if self.g.take().is_some() {
+            return match key_code {
+                KeyCode::Char('t') => vec![Message::GoToNextTab],
+                KeyCode::Char('T') => vec![Message::GoToPreviousTab],
+                _ => std::iter::once(Message::GoToFirstListItem)
+                    .chain(get_event_handling_msg(model, event))
+                    .collect(),
+            };
+        }
+
+        match get_event_handling_msg(model, event) {
+            Some(Message::GoToFirstListItem)
+                if model.active_pane == Pane::ResultsList && model.tab_count() > 1 =>
+            {
+                self.g = Some(Instant::now());
+                Vec::new()
+            }
+            msg => msg.into_iter().collect(),
+        }
+    }
+
+    /// Returns the message for a "g" that's waited too long for the next key.
+    pub(super) fn expired(&mut self) -> Option<Message> {
+        self.g
+            .take_if(|pressed_at| pressed_at.elapsed() >= PENDING_G_TIMEOUT)
+            .map(|_| Message::GoToFirstListItem)
+    }
+}
 
 pub fn get_event_handling_msg(model: &Model, event: Event) -> Option<Message> {
     match event {
@@ -14,13 +65,60 @@ pub fn get_event_handling_msg(model: &Model, event: Event) -> Option<Message> {
                 _ => None,
             },
             false => match key_event.kind {
+                KeyEventKind::Press if model.prompt.is_some() => match key_event.code {
+                    KeyCode::Enter => Some(Message::SubmitPrompt),
+                    KeyCode::Esc => Some(Message::CancelPrompt),
+                    KeyCode::Backspace => Some(Message::PromptBackspace),
+                    KeyCode::Tab => Some(Message::CompletePrompt),
+                    KeyCode::BackTab => Some(Message::CompletePromptBackwards),
+                    KeyCode::Char('c') if key_event.modifiers == KeyModifiers::CONTROL => {
+                        Some(Message::QuitImmediately)
+                    }
+                    KeyCode::Char('c') if key_event.modifiers == KeyModifiers::ALT => {
+                        Some(Message::ToggleSearchCaseSensitivity)
+                    }
+                    KeyCode::Char('r') if key_event.modifiers == KeyModifiers::ALT => {
+                        Some(Message::ToggleSearchRegex)
+                    }
+                    KeyCode::Char(c) => Some(Message::PromptInput(c)),
+                    _ => None,
+                },
+                // other panes close on Esc, as usual
+                KeyEventKind::Press
+                    if model.loading.is_some()
+                        && model.active_pane == Pane::ResultsList
+                        && key_event.code == KeyCode::Esc =>
+                {
+                    Some(Message::CancelFetch)
+                }
                 KeyEventKind::Press => match model.active_pane {
                     Pane::ResultsList => match key_event.code {
                         KeyCode::Enter => match model.results {
                             Ok(_) => Some(Message::UrlChosen),
                             Err(_) => None,
                         },
-                        KeyCode::Backspace => Some(Message::GoBack),
+                        KeyCode::Backspace | KeyCode::Char('H') => Some(Message::GoBack),
+                        KeyCode::Char('L') => Some(Message::GoForward),
+                        KeyCode::Char(' ') => Some(Message::ToggleMark),
+                        KeyCode::Char('v') => Some(Message::ToggleVisualMarking),
+                        KeyCode::Esc if model.visual_anchor.is_some() => {
+                            Some(Message::CancelVisualMarking)
+                        }
+                        KeyCode::Char('I') => Some(Message::InvertMarks),
+                        KeyCode::Char('a') => Some(Message::MarkAllVisible),
+                        KeyCode::Char('A') => Some(Message::ClearMarks),
+                        KeyCode::Char('e') => Some(Message::ExportMarks),
+                        KeyCode::Char('C') => Some(Message::CrawlMarks),
+                        KeyCode::Char(':') => Some(Message::OpenPrompt(PromptKind::Command)),
+                        KeyCode::Char('O') => Some(Message::OpenPrompt(PromptKind::OpenUrl)),
+                        KeyCode::Char('E') => model
+                            .get_selected_url()
+                            .map(|(url, _)| Message::OpenPromptWithInput(PromptKind::OpenUrl, url)),
+                        KeyCode::Char('t') => Some(Message::OpenTab),
+                        KeyCode::Char('x') => Some(Message::CloseTab),
+                        KeyCode::Char(c @ '1'..='9') => {
+                            Some(Message::GoToTab(c as usize - '1' as usize))
+                        }
                         KeyCode::Char('j') | KeyCode::Down => Some(Message::GoToNextListItem),
                         KeyCode::Char('k') | KeyCode::Up => Some(Message::GoToPreviousListItem),
                         KeyCode::Char('g') => Some(Message::GoToFirstListItem),
@@ -28,6 +126,22 @@ pub fn get_event_handling_msg(model: &Model, event: Event) -> Option<Message> {
                         KeyCode::Char('y') => Some(Message::YankUrlToClipboard),
                         KeyCode::Char('Y') => Some(Message::YankUrlsToClipboard),
                         KeyCode::Char('o') => Some(Message::OpenUrlInBrowser),
+                        KeyCode::Char('f') => Some(Message::OpenPrompt(PromptKind::Filter)),
+                        KeyCode::Char('/') => Some(Message::OpenPrompt(PromptKind::Search)),
+                        KeyCode::Char('n') => Some(Message::GoToNextSearchMatch),
+                        KeyCode::Char('N') => Some(Message::GoToPreviousSearchMatch),
+                        KeyCode::Char('F') => Some(Message::ClearFilter),
+                        KeyCode::Char('z') => Some(Message::OpenPrompt(PromptKind::FuzzyFilter)),
+                        KeyCode::Char('u') => Some(Message::ToggleRawUrls),
+                        KeyCode::Char('M') => Some(Message::ToggleMillerColumns),
+                        KeyCode::Char('P') => Some(Message::TogglePrefetch),
+                        KeyCode::Char('s') => Some(Message::CycleSortOrder),
+                        KeyCode::Char('d') => Some(Message::GoToPane(Pane::PageDetails)),
+                        KeyCode::Char('r') => Some(Message::GoToPane(Pane::Redirects)),
+                        KeyCode::Char('i') => Some(Message::GoToPane(Pane::Response)),
+                        KeyCode::Char('R') => Some(Message::GoToPane(Pane::Reader)),
+                        KeyCode::Char('V') => Some(Message::GoToPane(Pane::Source)),
+                        KeyCode::Char('T') => Some(Message::GoToPane(Pane::History)),
                         KeyCode::Char('?') => Some(Message::GoToPane(Pane::Help)),
                         KeyCode::Esc | KeyCode::Char('q') => Some(Message::GoBackOrQuit),
                         KeyCode::Char('c') => {
@@ -39,7 +153,117 @@ pub fn get_event_handling_msg(model: &Model, event: Event) -> Option<Message> {
                         }
                         _ => None,
                     },
+                    Pane::PageDetails => match key_event.code {
+                        KeyCode::Char('j') | KeyCode::Down => Some(Message::GoToNextListItem),
+                        KeyCode::Char('k') | KeyCode::Up => Some(Message::GoToPreviousListItem),
+                        KeyCode::Char('g') => Some(Message::GoToFirstListItem),
+                        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('d') => {
+                            Some(Message::GoBackOrQuit)
+                        }
+                        KeyCode::Char('c') => {
+                            if key_event.modifiers == KeyModifiers::CONTROL {
+                                Some(Message::QuitImmediately)
+                            } else {
+                                None
+                            }
+                        }
+                        _ => None,
+                    },
+                    Pane::Redirects => match key_event.code {
+                        KeyCode::Char('j') | KeyCode::Down => Some(Message::GoToNextListItem),
+                        KeyCode::Char('k') | KeyCode::Up => Some(Message::GoToPreviousListItem),
+                        KeyCode::Char('g') => Some(Message::GoToFirstListItem),
+                        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('r') => {
+                            Some(Message::GoBackOrQuit)
+                        }
+                        KeyCode::Char('c') => {
+                            if key_event.modifiers == KeyModifiers::CONTROL {
+                                Some(Message::QuitImmediately)
+                            } else {
+                                None
+                            }
+                        }
+                        _ => None,
+                    },
+                    Pane::Response => match key_event.code {
+                        KeyCode::Char('j') | KeyCode::Down => Some(Message::GoToNextListItem),
+                        KeyCode::Char('k') | KeyCode::Up => Some(Message::GoToPreviousListItem),
+                        KeyCode::Char('g') => Some(Message::GoToFirstListItem),
+                        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('i') => {
+                            Some(Message::GoBackOrQuit)
+                        }
+                        KeyCode::Char('c') => {
+                            if key_event.modifiers == KeyModifiers::CONTROL {
+                                Some(Message::QuitImmediately)
+                            } else {
+                                None
+                            }
+                        }
+                        _ => None,
+                    },
+                    Pane::Reader => match key_event.code {
+                        KeyCode::Char('j') | KeyCode::Down => Some(Message::GoToNextListItem),
+                        KeyCode::Char('k') | KeyCode::Up => Some(Message::GoToPreviousListItem),
+                        KeyCode::Char('g') => Some(Message::GoToFirstListItem),
+                        KeyCode::Char(c @ '1'..='9') => Some(Message::OpenPromptWithInput(
+                            PromptKind::ReaderLink,
+                            c.to_string(),
+                        )),
+                        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('R') => {
+                            Some(Message::GoBackOrQuit)
+                        }
+                        KeyCode::Char('c') => {
+                            if key_event.modifiers == KeyModifiers::CONTROL {
+                                Some(Message::QuitImmediately)
+                            } else {
+                                None
+                            }
+                        }
+                        _ => None,
+                    },
+                    Pane::Source => match key_event.code {
+                        KeyCode::Char('j') | KeyCode::Down => Some(Message::GoToNextListItem),
+                        KeyCode::Char('k') | KeyCode::Up => Some(Message::GoToPreviousListItem),
+                        KeyCode::Char('g') => Some(Message::GoToFirstListItem),
+                        KeyCode::Char('/') => Some(Message::OpenPrompt(PromptKind::SourceSearch)),
+                        KeyCode::Char('n') => Some(Message::GoToNextSearchMatch),
+                        KeyCode::Char('N') => Some(Message::GoToPreviousSearchMatch),
+                        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('V') => {
+                            Some(Message::GoBackOrQuit)
+                        }
+                        KeyCode::Char('c') => {
+                            if key_event.modifiers == KeyModifiers::CONTROL {
+                                Some(Message::QuitImmediately)
+                            } else {
+                                None
+                            }
+                        }
+                        _ => None,
+                    },
+                    Pane::History => match key_event.code {
+                        KeyCode::Enter => Some(Message::VisitChosen),
+                        KeyCode::Char('j') | KeyCode::Down => Some(Message::GoToNextListItem),
+                        KeyCode::Char('k') | KeyCode::Up => Some(Message::GoToPreviousListItem),
+                        KeyCode::Char('g') => Some(Message::GoToFirstListItem),
+                        KeyCode::Char('G') => Some(Message::GoToLastListItem),
+                        KeyCode::Char('m') => Some(Message::ExportVisitsAsMarkdown),
+                        KeyCode::Char('J') => Some(Message::ExportVisitsAsJson),
+                        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('T') => {
+                            Some(Message::GoBackOrQuit)
+                        }
+                        KeyCode::Char('c') => {
+                            if key_event.modifiers == KeyModifiers::CONTROL {
+                                Some(Message::QuitImmediately)
+                            } else {
+                                None
+                            }
+                        }
+                        _ => None,
+                    },
                     Pane::Help => match key_event.code {
+                        KeyCode::Char('j') | KeyCode::Down => Some(Message::GoToNextListItem),
+                        KeyCode::Char('k') | KeyCode::Up => Some(Message::GoToPreviousListItem),
+                        KeyCode::Char('g') => Some(Message::GoToFirstListItem),
                         KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('?') => {
                             Some(Message::GoBackOrQuit)
                         }
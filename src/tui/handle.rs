@@ -1,18 +1,39 @@
 use super::command::Command;
 use super::message::Message;
-use crate::service::fetch_urls;
+use crate::service::{FetchTimedOut, fetch_urls};
 use arboard::Clipboard;
+use std::io::Write;
 use tokio::sync::mpsc::Sender;
 
 pub(super) async fn handle_command(command: Command, event_tx: Sender<Message>) {
     match command {
-        Command::GetResults { current, chosen } => {
+        Command::GetResults {
+            request_id,
+            url,
+            options,
+            cancel,
+        } => {
             tokio::spawn(async move {
-                let page = fetch_urls(&chosen).await.map_err(|e| e.to_string());
-                let _ = event_tx.try_send(Message::ResultsFetched {
-                    previous: current,
-                    page_result: page,
-                });
+                // dropping the fetch's future aborts it
+                let result = tokio::select! {
+                    Ok(()) = cancel => return,
+                    result = fetch_urls(&url, &options) => result,
+                };
+
+                let message = match result {
+                    Err(e) if e.is::<FetchTimedOut>() => Message::FetchTimedOut {
+                        request_id,
+                        url,
+                        error: e.to_string(),
+                    },
+                    result => Message::ResultsFetched {
+                        request_id,
+                        page_result: result.map(Box::new).map_err(|e| e.to_string()),
+                    },
+                };
+                // unlike the other results, these mustn't be dropped when the
+                // channel is full: the fetch would be tracked as ongoing forever
+                let _ = event_tx.send(message).await;
             });
         }
         Command::YankContentToClipboard(content) => {
@@ -28,9 +49,33 @@ pub(super) async fn handle_command(command: Command, event_tx: Sender<Message>)
                 let _ = event_tx.try_send(Message::UrlOpened(result));
             });
         }
+        Command::WriteToFile {
+            path,
+            contents,
+            append,
+        } => {
+            tokio::task::spawn_blocking(move || {
+                let result = write_to_file(&path, &contents, append);
+
+                let _ = event_tx.try_send(Message::FileWritten { path, result });
+            });
+        }
     }
 }
 
+fn write_to_file(path: &str, contents: &str, append: bool) -> anyhow::Result<()> {
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(append)
+        .truncate(!append)
+        .open(path)?;
+
+    file.write_all(contents.as_bytes())?;
+
+    Ok(())
+}
+
 fn copy_content_to_clipboard(content: &str) -> anyhow::Result<()> {
     let mut clipboard = Clipboard::new()?;
 
@@ -0,0 +1,241 @@
+use super::common::*;
+use ratatui::{
+    style::Style,
+    text::{Line, Span},
+};
+use std::ops::Range;
+
+// elements whose contents aren't markup, and so shouldn't be highlighted as such
+const RAW_TEXT_ELEMENTS: [&str; 2] = ["script", "style"];
+
+#[derive(Clone, Copy, PartialEq)]
+enum State {
+    Text,
+    TagName,
+    Tag,
+    AttrValue(char),
+    Comment,
+    RawText(&'static str),
+}
+
+/// Highlights the lines of an HTML document that fall in `range`.
+///
+/// The whole document up to the end of the range is scanned, since the
+/// highlighting of a line depends on the ones before it (eg, for multi-line
+/// tags and comments).
+pub(super) fn highlight_html(source: &str, range: Range<usize>) -> Vec<Line<'static>> {
+    let mut highlighter = Highlighter {
+        state: State::Text,
+        tag_name: String::new(),
+        spans: Vec::new(),
+        run: String::new(),
+        run_style: Style::new(),
+    };
+
+    let mut lines = Vec::new();
+    for (index, line) in source.lines().enumerate().take(range.end) {
+        highlighter.highlight_line(line, range.contains(&index));
+        if range.contains(&index) {
+            lines.push(Line::from(highlighter.take_spans()));
+        }
+    }
+
+    lines
+}
+
+struct Highlighter {
+    state: State,
+    tag_name: String,
+    spans: Vec<Span<'static>>,
+    run: String,
+    run_style: Style,
+}
+
+impl Highlighter {
+    fn highlight_line(&mut self, line: &str, emit: bool) {
+        let mut rest = line;
+        while let Some(c) = rest.chars().next() {
+            let (token, style) = self.next_token(rest, c);
+            if emit {
+                self.push(token, style);
+            }
+            rest = &rest[token.len()..];
+        }
+    }
+
+    fn next_token<'a>(&mut self, rest: &'a str, c: char) -> (&'a str, Style) {
+        let punctuation = Style::new().fg(SOURCE_PUNCTUATION_COLOR);
+        let single = &rest[..c.len_utf8()];
+        match self.state {
+            State::Text => {
+                if rest.starts_with("<!--") {
+                    self.state = State::Comment;
+                    (&rest[..4], Style::new().fg(SOURCE_COMMENT_COLOR))
+                } else if c == '<' {
+                    self.state = State::TagName;
+                    self.tag_name.clear();
+                    (single, punctuation)
+                } else {
+                    (single, Style::new())
+                }
+            }
+            State::TagName => match c {
+                '>' => {
+                    self.end_tag();
+                    (single, punctuation)
+                }
+                c if c.is_whitespace() => {
+                    self.state = State::Tag;
+                    (single, Style::new())
+                }
+                // kept in the name so that closing tags aren't taken for
+                // opening ones
+                '/' | '!' | '?' if self.tag_name.is_empty() => {
+                    self.tag_name.push(c);
+                    (single, punctuation)
+                }
+                c => {
+                    self.tag_name.push(c.to_ascii_lowercase());
+                    (single, Style::new().fg(SOURCE_TAG_COLOR))
+                }
+            },
+            State::Tag => match c {
+                '>' => {
+                    self.end_tag();
+                    (single, punctuation)
+                }
+                '"' | '\'' => {
+                    self.state = State::AttrValue(c);
+                    (single, Style::new().fg(SOURCE_VALUE_COLOR))
+                }
+                '=' | '/' => (single, punctuation),
+                _ => (single, Style::new().fg(SOURCE_ATTRIBUTE_COLOR)),
+            },
+            State::AttrValue(quote) => {
+                if c == quote {
+                    self.state = State::Tag;
+                }
+                (single, Style::new().fg(SOURCE_VALUE_COLOR))
+            }
+            State::Comment => {
+                if rest.starts_with("-->") {
+                    self.state = State::Text;
+                    (&rest[..3], Style::new().fg(SOURCE_COMMENT_COLOR))
+                } else {
+                    (single, Style::new().fg(SOURCE_COMMENT_COLOR))
+                }
+            }
+            State::RawText(name) => {
+                let closes = rest.starts_with("</")
+                    && rest
+                        .get(2..name.len() + 2)
+                        .is_some_and(|s| s.eq_ignore_ascii_case(name));
+                if closes {
+                    self.state = State::TagName;
+                    self.tag_name.clear();
+                    (&rest[..1], punctuation)
+                } else {
+                    (single, Style::new().fg(SOURCE_RAW_TEXT_COLOR))
+                }
+            }
+        }
+    }
+
+    fn end_tag(&mut self) {
+        self.state = RAW_TEXT_ELEMENTS
+            .iter()
+            .find(|name| **name == self.tag_name)
+            .map_or(State::Text, |name| State::RawText(name));
+    }
+
+    fn push(&mut self, token: &str, style: Style) {
+        if style != self.run_style {
+            self.flush();
+            self.run_style = style;
+        }
+        // tabs aren't rendered consistently across terminals
+        self.run.push_str(&token.replace('\t', "    "));
+    }
+
+    fn flush(&mut self) {
+        if !self.run.is_empty() {
+            let run = std::mem::take(&mut self.run);
+            self.spans.push(Span::styled(run, self.run_style));
+        }
+    }
+
+    fn take_spans(&mut self) -> Vec<Span<'static>> {
+        self.flush();
+        std::mem::take(&mut self.spans)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::Color;
+
+    // the text and color of each span, line by line
+    fn highlighted(source: &str, range: Range<usize>) -> Vec<Vec<(String, Option<Color>)>> {
+        highlight_html(source, range)
+            .into_iter()
+            .map(|line| {
+                line.spans
+                    .into_iter()
+                    .map(|span| (span.content.to_string(), span.style.fg))
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn span(text: &str, color: Option<Color>) -> (String, Option<Color>) {
+        (text.to_string(), color)
+    }
+
+    #[test]
+    fn tags_attributes_and_values_are_highlighted() {
+        let punctuation = Some(SOURCE_PUNCTUATION_COLOR);
+        let tag = Some(SOURCE_TAG_COLOR);
+
+        assert_eq!(
+            highlighted(r#"<a href="/x">link</a>"#, 0..1),
+            [[
+                span("<", punctuation),
+                span("a", tag),
+                span(" ", None),
+                span("href", Some(SOURCE_ATTRIBUTE_COLOR)),
+                span("=", punctuation),
+                span(r#""/x""#, Some(SOURCE_VALUE_COLOR)),
+                span(">", punctuation),
+                span("link", None),
+                span("</", punctuation),
+                span("a", tag),
+                span(">", punctuation),
+            ]]
+        );
+    }
+
+    #[test]
+    fn state_carries_over_from_lines_before_the_range() {
+        assert_eq!(
+            highlighted("<!-- a\nb -->\ntext\n", 1..3),
+            [
+                vec![span("b -->", Some(SOURCE_COMMENT_COLOR))],
+                vec![span("text", None)],
+            ]
+        );
+    }
+
+    #[test]
+    fn script_contents_are_not_highlighted_as_markup() {
+        assert_eq!(
+            highlighted("<script>\nif (a <b) {}\n</script>", 1..2),
+            [[span("if (a <b) {}", Some(SOURCE_RAW_TEXT_COLOR))]]
+        );
+    }
+
+    #[test]
+    fn tabs_are_expanded() {
+        assert_eq!(highlighted("\tx", 0..1), [[span("    x", None)]]);
+    }
+}
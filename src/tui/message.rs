@@ -1,5 +1,7 @@
+use super::cmdline::Setting;
 use super::common::Pane;
-use crate::domain::Page;
+use super::model::PromptKind;
+use crate::domain::{LinkOrder, Page};
 
 pub(crate) enum Message {
     TerminalResize(u16, u16),
@@ -9,16 +11,66 @@ pub(crate) enum Message {
     GoToLastListItem,
     YankUrlToClipboard,
     YankUrlsToClipboard,
+    ToggleMark,
+    ToggleVisualMarking,
+    CancelVisualMarking,
+    InvertMarks,
+    MarkAllVisible,
+    ClearMarks,
+    ExportMarks,
+    CrawlMarks,
     OpenUrlInBrowser,
     GoToPane(Pane),
+    OpenPrompt(PromptKind),
+    OpenPromptWithInput(PromptKind, String),
+    PromptInput(char),
+    PromptBackspace,
+    CompletePrompt,
+    CompletePromptBackwards,
+    SubmitPrompt,
+    CancelPrompt,
+    ClearFilter,
+    ToggleRawUrls,
+    ToggleMillerColumns,
+    TogglePrefetch,
+    CycleSortOrder,
+    SetSortOrder(LinkOrder),
+    Set(Setting, Option<bool>),
+    OpenUrl(String),
+    ApplyFilter(String),
+    Refresh,
+    Bookmark,
+    GoToNextSearchMatch,
+    GoToPreviousSearchMatch,
+    ToggleSearchCaseSensitivity,
+    ToggleSearchRegex,
     GoBack,
+    GoForward,
     UrlChosen,
+    OpenTab,
+    CloseTab,
+    GoToNextTab,
+    GoToPreviousTab,
+    GoToTab(usize),
+    VisitChosen,
+    ExportVisitsAsMarkdown,
+    ExportVisitsAsJson,
     ResultsFetched {
-        previous: String,
-        page_result: Result<Page, String>,
+        request_id: u64,
+        page_result: Result<Box<Page>, String>,
     },
+    FetchTimedOut {
+        request_id: u64,
+        url: String,
+        error: String,
+    },
+    CancelFetch,
     ContentYanked(anyhow::Result<()>),
     UrlOpened(anyhow::Result<()>),
+    FileWritten {
+        path: String,
+        result: anyhow::Result<()>,
+    },
     GoBackOrQuit,
     QuitImmediately,
 }
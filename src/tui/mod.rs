@@ -1,11 +1,21 @@
 mod app;
+mod cmdline;
 mod command;
 mod common;
 mod event;
 mod handle;
+mod highlight;
 mod message;
 mod model;
+mod prefetch;
+mod search;
 mod update;
 mod view;
+mod visits;
+mod wrap;
 
 pub use app::*;
+pub use prefetch::{
+    DEFAULT_PREFETCH_CONCURRENCY, DEFAULT_PREFETCH_NEIGHBORS, DEFAULT_PREFETCH_PER_HOST,
+    MAX_PREFETCH_NEIGHBORS, PrefetchOptions,
+};
@@ -1,9 +1,19 @@
+use super::app::TuiConfig;
 use super::common::{MIN_TERMINAL_HEIGHT, MIN_TERMINAL_WIDTH, Pane, TerminalDimensions};
-use crate::domain::{Page, PageDetails};
+use super::prefetch::PrefetchOptions;
+use super::search::{Search, fuzzy_matches};
+use super::visits::Visits;
+use crate::domain::{Link, LinkOrder, Page, PageDetails, UrlFilter};
+use crate::service::{FetchOptions, anchor_source_lines};
 use ratatui::widgets::ListState;
 use std::collections::HashMap;
 use std::collections::VecDeque;
+use std::ops::RangeInclusive;
+use std::time::Instant;
+use tokio::sync::oneshot;
 const USER_MESSAGE_DEFAULT_FRAMES: u16 = 4;
+// lines shown above the one scrolled to in the page source
+const SOURCE_CONTEXT_LINES: usize = 3;
 
 #[derive(Debug, Default, PartialEq, Eq)]
 pub enum RunningState {
@@ -46,37 +56,176 @@ impl UserMessage {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptKind {
+    Filter,
+    ReaderLink,
+    SourceSearch,
+    Search,
+    FuzzyFilter,
+    OpenUrl,
+    Command,
+}
+
+impl PromptKind {
+    pub(super) fn label(&self) -> &'static str {
+        match self {
+            PromptKind::Filter => "filter",
+            PromptKind::ReaderLink => "follow link #",
+            PromptKind::SourceSearch => "search source",
+            PromptKind::Search => "search",
+            PromptKind::FuzzyFilter => "fuzzy filter",
+            PromptKind::OpenUrl => "open",
+            PromptKind::Command => "command",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Prompt {
+    pub kind: PromptKind,
+    pub input: String,
+    /// input the prompt was opened with
+    pub initial_input: String,
+    pub completion: Option<Completion>,
+}
+
+/// Candidates cycled through when completing a prompt's input.
+#[derive(Debug)]
+pub struct Completion {
+    pub candidates: Vec<String>,
+    pub index: usize,
+}
+
+impl Prompt {
+    pub(super) fn new(kind: PromptKind, input: String) -> Self {
+        Self {
+            kind,
+            initial_input: input.clone(),
+            input,
+            completion: None,
+        }
+    }
+
+    /// Replaces the input with the next completion candidate (or the
+    /// previous one, when `forward` is false). Candidates are looked up with
+    /// `candidates` when not already cycling through them.
+    ///
+    /// Returns false if there are no candidates.
+    pub(super) fn complete(
+        &mut self,
+        forward: bool,
+        candidates: impl FnOnce(&str) -> Vec<String>,
+    ) -> bool {
+        let completion = match self.completion.take() {
+            Some(mut c) => {
+                let count = c.candidates.len();
+                c.index = match forward {
+                    true => (c.index + 1) % count,
+                    false => (c.index + count - 1) % count,
+                };
+                c
+            }
+            None => {
+                let candidates = candidates(&self.input);
+                if candidates.is_empty() {
+                    return false;
+                }
+                let index = if forward { 0 } else { candidates.len() - 1 };
+                Completion { candidates, index }
+            }
+        };
+
+        self.input = completion.candidates[completion.index].clone();
+        self.completion = Some(completion);
+        true
+    }
+}
+
+/// A page being fetched.
+#[derive(Debug)]
+pub struct Loading {
+    /// identifies the fetch, so that its results can be told apart from those
+    /// of earlier ones
+    pub id: u64,
+    pub url: String,
+    pub started_at: Instant,
+    cancel: oneshot::Sender<()>,
+}
+
+impl Loading {
+    /// Returns the loading state, along with the receiving end of its
+    /// cancellation channel (to be handed to the fetch).
+    fn new(id: u64, url: &str) -> (Self, oneshot::Receiver<()>) {
+        let (cancel, cancelled) = oneshot::channel();
+        let loading = Self {
+            id,
+            url: url.to_string(),
+            started_at: Instant::now(),
+            cancel,
+        };
+
+        (loading, cancelled)
+    }
+
+    pub(super) fn cancel(self) {
+        let _ = self.cancel.send(());
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct Results {
-    pub(crate) items: Vec<String>,
+    pub(crate) items: Vec<Link>,
+    pub(crate) total: usize,
     pub(crate) state: ListState,
 }
 
 impl Default for Results {
     fn default() -> Self {
-        let items: Vec<String> = Vec::new();
+        let items: Vec<Link> = Vec::new();
         let state = ListState::default().with_selected(Some(0));
 
-        Self { items, state }
+        Self {
+            items,
+            total: 0,
+            state,
+        }
     }
 }
 
-impl From<&Vec<String>> for Results {
-    fn from(value: &Vec<String>) -> Self {
-        let items = value.iter().map(|line| line.to_string()).collect();
+impl Results {
+    /// `fuzzy_filter` narrows down the links left after applying `filter`; see
+    /// [`fuzzy_matches`].
+    pub(super) fn new(
+        page: &Page,
+        filter: &UrlFilter,
+        fuzzy_filter: &str,
+        order: LinkOrder,
+    ) -> Self {
+        let items = page
+            .visible_links(filter, order)
+            .into_iter()
+            .filter(|link| fuzzy_matches(fuzzy_filter, link))
+            .cloned()
+            .collect();
         let state = ListState::default().with_selected(Some(0));
 
-        Self { items, state }
+        Self {
+            items,
+            total: page.links.len(),
+            state,
+        }
     }
 }
 
-impl From<&Page> for Results {
-    fn from(page: &Page) -> Self {
-        let items = page.page_urls.iter().map(|line| line.to_string()).collect();
-        let state = ListState::default().with_selected(Some(0));
-
-        Self { items, state }
-    }
+/// The state of a tab while another one is active.
+pub(crate) struct Tab {
+    page_details: PageDetails,
+    results: Result<Results, String>,
+    history: VecDeque<String>,
+    forward_history: Vec<String>,
+    visits: Visits,
+    visits_state: ListState,
 }
 
 pub(crate) struct Model {
@@ -84,7 +233,60 @@ pub(crate) struct Model {
     pub page_details: PageDetails,
     pub results: Result<Results, String>,
     pub results_cache: HashMap<String, Page>,
+    /// the fetch whose results are awaited, if any
+    pub loading: Option<Loading>,
+    pub last_request_id: u64,
+    /// pages to go back to, the most recent one last
     pub history: VecDeque<String>,
+    /// pages to go forward to (after going back), the next one last
+    pub forward_history: Vec<String>,
+    /// selected result of each page, to restore when returning to it
+    pub selections: HashMap<String, String>,
+    /// every page visited, including ones on branches navigated away from
+    pub visits: Visits,
+    pub visits_state: ListState,
+    /// tabs other than the active one, in order
+    pub tabs: Vec<Tab>,
+    /// position of the active tab among all of them
+    pub active_tab: usize,
+    /// marked URLs, in the order they were marked
+    pub marks: Vec<String>,
+    /// where a range of results to mark starts, while one is being selected
+    pub visual_anchor: Option<usize>,
+    /// marked URLs left to fetch in the background
+    pub crawl_queue: VecDeque<String>,
+    /// whether to show the previous page and a preview of the selected link
+    /// alongside the results
+    pub miller_columns: bool,
+    /// the fetch of the selected link's page for previewing, if any
+    pub preview: Option<Loading>,
+    pub prefetch: PrefetchOptions,
+    /// background fetches of the pages of links near the cursor
+    pub prefetches: Vec<Loading>,
+    /// background fetches of the pages of marked links being crawled; these
+    /// outlive prefetching being turned off
+    pub crawls: Vec<Loading>,
+    /// why pages fetched in the background (previews, prefetches and crawls)
+    /// couldn't be shown
+    pub background_failures: HashMap<String, String>,
+    pub filter: UrlFilter,
+    pub fuzzy_filter: String,
+    pub order: LinkOrder,
+    pub fetch_options: FetchOptions,
+    pub no_follow_rels: Vec<String>,
+    pub show_raw_urls: bool,
+    pub prompt: Option<Prompt>,
+    pub search: Search,
+    /// selected result when the search prompt was opened; matches are looked
+    /// for from there while typing
+    pub search_origin: Option<usize>,
+    /// scroll offset of the active pane, when it's not the results list
+    pub pane_scroll: u16,
+    /// lines of the current page's source holding the selected link
+    pub source_highlight: Vec<usize>,
+    pub source_search: Option<String>,
+    /// line of the source search match last jumped to
+    pub source_match: Option<usize>,
     pub debug: bool,
     pub event_counter: u64,
     pub last_active_pane: Option<Pane>,
@@ -96,12 +298,25 @@ pub(crate) struct Model {
 }
 
 impl Model {
-    pub(crate) fn new(page: Page, terminal_dimensions: TerminalDimensions, debug: bool) -> Self {
+    pub(crate) fn new(
+        page: Page,
+        config: TuiConfig,
+        terminal_dimensions: TerminalDimensions,
+        debug: bool,
+    ) -> Self {
+        let TuiConfig {
+            filter,
+            order,
+            fetch_options,
+            no_follow_rels,
+            prefetch,
+        } = config;
         let terminal_too_small = terminal_dimensions.width < MIN_TERMINAL_WIDTH
             || terminal_dimensions.height < MIN_TERMINAL_HEIGHT;
-        let results = Ok(Results::from(&page.page_urls));
+        let results = Ok(Results::new(&page, &filter, "", order));
         let mut results_cache = HashMap::new();
         let page_details = page.details.clone();
+        let visits = Visits::new(&page.details.url);
         results_cache.insert(page.details.url.clone(), page);
 
         Self {
@@ -109,7 +324,37 @@ impl Model {
             page_details,
             results,
             results_cache,
+            loading: None,
+            last_request_id: 0,
             history: VecDeque::new(),
+            forward_history: Vec::new(),
+            selections: HashMap::new(),
+            visits,
+            visits_state: ListState::default(),
+            tabs: Vec::new(),
+            active_tab: 0,
+            marks: Vec::new(),
+            visual_anchor: None,
+            crawl_queue: VecDeque::new(),
+            miller_columns: false,
+            preview: None,
+            prefetch,
+            prefetches: Vec::new(),
+            crawls: Vec::new(),
+            background_failures: HashMap::new(),
+            filter,
+            fuzzy_filter: String::new(),
+            order,
+            fetch_options,
+            no_follow_rels,
+            show_raw_urls: false,
+            prompt: None,
+            search: Search::default(),
+            search_origin: None,
+            pane_scroll: 0,
+            source_highlight: Vec::new(),
+            source_search: None,
+            source_match: None,
             debug,
             event_counter: 0,
             last_active_pane: None,
@@ -120,11 +365,57 @@ impl Model {
             user_message: None,
         }
     }
+
+    /// Returns the loading state for a new fetch of `url` (to be set as the
+    /// awaited one, or as a background fetch), along with the receiving end of
+    /// its cancellation channel.
+    pub(super) fn start_loading(&mut self, url: &str) -> (Loading, oneshot::Receiver<()>) {
+        self.last_request_id += 1;
+        Loading::new(self.last_request_id, url)
+    }
+
+    pub(super) fn is_awaiting(&self, request_id: u64) -> bool {
+        self.loading.as_ref().is_some_and(|l| l.id == request_id)
+    }
+
+    /// Stops tracking the background fetch (a preview, a prefetch or a crawl)
+    /// matching `predicate`, and returns it.
+    pub(super) fn take_background_fetch(
+        &mut self,
+        predicate: impl Fn(&Loading) -> bool,
+    ) -> Option<Loading> {
+        if self.preview.as_ref().is_some_and(&predicate) {
+            return self.preview.take();
+        }
+
+        if let Some(index) = self.prefetches.iter().position(&predicate) {
+            return Some(self.prefetches.swap_remove(index));
+        }
+
+        let index = self.crawls.iter().position(predicate)?;
+        Some(self.crawls.swap_remove(index))
+    }
+
+    pub(super) fn is_being_fetched(&self, url: &str) -> bool {
+        self.loading
+            .iter()
+            .chain(&self.preview)
+            .chain(&self.prefetches)
+            .chain(&self.crawls)
+            .any(|l| l.url == url)
+    }
+
     pub(super) fn go_back_or_quit(&mut self) {
         let active_pane = Some(self.active_pane);
         match self.active_pane {
             Pane::ResultsList => self.running_state = RunningState::Done,
-            Pane::Help => self.active_pane = self.last_active_pane.unwrap_or(Pane::ResultsList),
+            Pane::PageDetails
+            | Pane::Redirects
+            | Pane::Response
+            | Pane::Reader
+            | Pane::Source
+            | Pane::History
+            | Pane::Help => self.active_pane = self.last_active_pane.unwrap_or(Pane::ResultsList),
         }
 
         self.last_active_pane = active_pane;
@@ -135,42 +426,738 @@ impl Model {
             Pane::ResultsList => {
                 if let Ok(r) = &mut self.results {
                     r.state.select_next();
+                    clamp_selection(&mut r.state, r.items.len());
                 }
             }
-            Pane::Help => {}
+            Pane::History => {
+                self.visits_state.select_next();
+                clamp_selection(&mut self.visits_state, self.visits.rows().len());
+            }
+            Pane::PageDetails
+            | Pane::Redirects
+            | Pane::Response
+            | Pane::Reader
+            | Pane::Source
+            | Pane::Help => self.pane_scroll = self.pane_scroll.saturating_add(1),
         }
     }
 
     pub(super) fn select_previous_list_item(&mut self) {
-        if self.active_pane == Pane::ResultsList
-            && let Ok(r) = &mut self.results
-        {
-            r.state.select_previous();
+        match self.active_pane {
+            Pane::ResultsList => {
+                if let Ok(r) = &mut self.results {
+                    r.state.select_previous();
+                    clamp_selection(&mut r.state, r.items.len());
+                }
+            }
+            Pane::History => {
+                self.visits_state.select_previous();
+                clamp_selection(&mut self.visits_state, self.visits.rows().len());
+            }
+            Pane::PageDetails
+            | Pane::Redirects
+            | Pane::Response
+            | Pane::Reader
+            | Pane::Source
+            | Pane::Help => self.pane_scroll = self.pane_scroll.saturating_sub(1),
         }
     }
 
     pub(super) fn select_first_list_item(&mut self) {
-        if self.active_pane == Pane::ResultsList
-            && let Ok(r) = &mut self.results
-        {
-            r.state.select_first();
+        match self.active_pane {
+            Pane::ResultsList => {
+                if let Ok(r) = &mut self.results {
+                    r.state.select_first();
+                }
+            }
+            Pane::History => self.visits_state.select_first(),
+            Pane::PageDetails
+            | Pane::Redirects
+            | Pane::Response
+            | Pane::Reader
+            | Pane::Source
+            | Pane::Help => self.pane_scroll = 0,
         }
     }
     pub(super) fn select_last_list_item(&mut self) {
-        if self.active_pane == Pane::ResultsList
-            && let Ok(r) = &mut self.results
-        {
-            r.state.select_last();
+        match self.active_pane {
+            Pane::ResultsList => {
+                if let Ok(r) = &mut self.results {
+                    r.state.select(r.items.len().checked_sub(1));
+                }
+            }
+            Pane::History => self
+                .visits_state
+                .select(self.visits.rows().len().checked_sub(1)),
+            _ => {}
+        }
+    }
+
+    /// Finds the lines of the current page's source holding the selected link,
+    /// and scrolls to the first of them.
+    pub(super) fn locate_selected_link_in_source(&mut self) {
+        self.source_highlight = match (
+            self.get_selected_link(),
+            self.results_cache.get(&self.page_details.url),
+        ) {
+            (Some(link), Some(page)) => {
+                anchor_source_lines(page, &link.url, &self.fetch_options.normalize)
+            }
+            _ => Vec::new(),
+        };
+
+        if let Some(&line) = self.source_highlight.first() {
+            self.scroll_source_to(line);
         }
     }
 
+    /// Scrolls to the next line of the current page's source matching the
+    /// search query (or the previous one, when `forward` is false), wrapping
+    /// around at either end. Matches are looked for relative to the last one
+    /// jumped to, or to the top of the pane if there's none.
+    pub(super) fn go_to_source_search_match(&mut self, forward: bool) {
+        let Some(query) = self.source_search.as_ref().map(|q| q.to_lowercase()) else {
+            return;
+        };
+        let Some(page) = self.results_cache.get(&self.page_details.url) else {
+            return;
+        };
+
+        let matches: Vec<usize> = page
+            .source
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| line.to_lowercase().contains(&query))
+            .map(|(i, _)| i)
+            .collect();
+        let found = match (forward, self.source_match) {
+            (true, Some(current)) => matches.iter().find(|&&l| l > current),
+            (true, None) => matches.iter().find(|&&l| l >= self.pane_scroll as usize),
+            (false, current) => {
+                let current = current.unwrap_or(self.pane_scroll as usize);
+                matches.iter().rev().find(|&&l| l < current)
+            }
+        };
+        let found = match forward {
+            true => found.or(matches.first()),
+            false => found.or(matches.last()),
+        };
+
+        match found {
+            Some(&line) => {
+                self.source_match = Some(line);
+                self.scroll_source_to(line);
+            }
+            None => self.user_message = Some(UserMessage::error("no matches in page source")),
+        }
+    }
+
+    fn scroll_source_to(&mut self, line: usize) {
+        self.pane_scroll = line
+            .saturating_sub(SOURCE_CONTEXT_LINES)
+            .min(u16::MAX as usize) as u16;
+    }
+
     pub fn get_selected_url(&self) -> Option<(String, usize)> {
         match &self.results {
             Ok(r) => {
                 let index = r.state.selected()?;
-                r.items.get(index).map(|si| (si.clone(), index))
+                r.items.get(index).map(|link| (link.url.clone(), index))
             }
             Err(_) => None,
         }
     }
+
+    pub(super) fn get_selected_link(&self) -> Option<&Link> {
+        let results = self.results.as_ref().ok()?;
+        results.items.get(results.state.selected()?)
+    }
+
+    /// Returns the first rel value of the selected link that's configured to
+    /// not be followed, if any.
+    pub(super) fn selected_link_no_follow_rel(&self) -> Option<&str> {
+        self.link_no_follow_rel(self.get_selected_link()?)
+    }
+
+    pub(super) fn link_no_follow_rel(&self, link: &Link) -> Option<&str> {
+        self.no_follow_rels
+            .iter()
+            .find(|r| link.has_rel(r))
+            .map(|r| r.as_str())
+    }
+
+    /// Selects the result matching the search that comes after `from` (or
+    /// before it, when `forward` is false), wrapping around at either end.
+    /// `from` itself is considered when `inclusive` is set.
+    ///
+    /// Returns whether a match was found.
+    pub(super) fn select_search_match(
+        &mut self,
+        from: usize,
+        forward: bool,
+        inclusive: bool,
+    ) -> bool {
+        let Ok(results) = &mut self.results else {
+            return false;
+        };
+
+        let matches: Vec<usize> = results
+            .items
+            .iter()
+            .enumerate()
+            .filter(|(_, link)| self.search.matches(link, self.show_raw_urls))
+            .map(|(i, _)| i)
+            .collect();
+        let found = match forward {
+            true => matches
+                .iter()
+                .find(|&&i| i > from || (inclusive && i == from))
+                .or(matches.first()),
+            false => matches
+                .iter()
+                .rev()
+                .find(|&&i| i < from || (inclusive && i == from))
+                .or(matches.last()),
+        };
+
+        match found {
+            Some(&index) => {
+                results.state.select(Some(index));
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub(super) fn has_search_matches(&self) -> bool {
+        self.results.as_ref().is_ok_and(|r| {
+            r.items
+                .iter()
+                .any(|link| self.search.matches(link, self.show_raw_urls))
+        })
+    }
+
+    /// Moves the selection as the search query is being edited.
+    pub(super) fn update_search_selection(&mut self) {
+        let Some(origin) = self.search_origin else {
+            return;
+        };
+
+        if !self.select_search_match(origin, true, true)
+            && let Ok(results) = &mut self.results
+        {
+            results.state.select(Some(origin));
+        }
+    }
+
+    /// Shows a page from the cache, restoring the selection it had when it was
+    /// last shown. Returns false if the page isn't cached.
+    pub(super) fn show_cached_page(&mut self, url: &str) -> bool {
+        let Some(page) = self.results_cache.get(url) else {
+            return false;
+        };
+
+        let mut results = Results::new(page, &self.filter, &self.fuzzy_filter, self.order);
+        if let Some(index) = self
+            .selections
+            .get(url)
+            .and_then(|selected| results.items.iter().position(|l| &l.url == selected))
+        {
+            results.state.select(Some(index));
+        }
+        self.page_details = page.details.clone();
+        self.results = Ok(results);
+        self.pane_scroll = 0;
+        self.visual_anchor = None;
+
+        true
+    }
+
+    /// Returns the URLs visited or fetched so far that start with `input`
+    /// (ignoring their scheme, if `input` has none), most recently visited
+    /// first.
+    pub(super) fn url_completions(&self, input: &str) -> Vec<String> {
+        let mut cached: Vec<&String> = self.results_cache.keys().collect();
+        cached.sort();
+
+        let mut completions: Vec<String> = Vec::new();
+        for url in self
+            .history
+            .iter()
+            .rev()
+            .chain(self.forward_history.iter().rev())
+            .chain(cached)
+        {
+            let without_scheme = url.split_once("://").map_or(url.as_str(), |(_, rest)| rest);
+            if (url.starts_with(input) || without_scheme.starts_with(input))
+                && !completions.contains(url)
+            {
+                completions.push(url.clone());
+            }
+        }
+
+        completions
+    }
+
+    pub(super) fn is_marked(&self, url: &str) -> bool {
+        self.marks.iter().any(|m| m == url)
+    }
+
+    pub(super) fn toggle_mark(&mut self, url: &str) {
+        match self.marks.iter().position(|m| m == url) {
+            Some(index) => {
+                self.marks.remove(index);
+            }
+            None => self.marks.push(url.to_string()),
+        }
+    }
+
+    pub(super) fn mark(&mut self, url: &str) {
+        if !self.is_marked(url) {
+            self.marks.push(url.to_string());
+        }
+    }
+
+    /// Returns the results between the start of the range being selected and
+    /// the selected one.
+    pub(super) fn visual_range(&self) -> Option<RangeInclusive<usize>> {
+        let anchor = self.visual_anchor?;
+        let (_, selected) = self.get_selected_url()?;
+        Some(anchor.min(selected)..=anchor.max(selected))
+    }
+
+    pub(super) fn visible_urls(&self) -> Vec<String> {
+        match &self.results {
+            Ok(r) => r.items.iter().map(|link| link.url.clone()).collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    /// Moves from the current page to the cached page at `url`, as a new step
+    /// in the navigation history.
+    pub(super) fn navigate_to(&mut self, url: &str) {
+        // when showing an error, the current page was pushed already
+        if self.results.is_ok() {
+            self.remember_selection();
+            self.history.push_back(self.page_details.url.clone());
+        }
+        self.forward_history.clear();
+        self.visits.visit(url);
+        self.show_cached_page(url);
+    }
+
+    /// Moves to the page visited at `index` in the tree of visits; the
+    /// navigation history becomes the path leading to it.
+    pub(super) fn jump_to_visit(&mut self, index: usize) -> bool {
+        let Some(url) = self.visits.url(index).map(|u| u.to_string()) else {
+            return false;
+        };
+        if !self.results_cache.contains_key(&url) {
+            return false;
+        }
+
+        if self.results.is_ok() {
+            self.remember_selection();
+        }
+        self.history = self.visits.ancestor_urls(index).into();
+        self.forward_history.clear();
+        self.visits.go_to(index);
+        self.show_cached_page(&url)
+    }
+
+    /// Opens a new tab showing the current page, with a history of its own,
+    /// right after the active one, and switches to it.
+    pub(super) fn open_tab(&mut self) {
+        let url = self.page_details.url.clone();
+        let tab = Tab {
+            page_details: self.page_details.clone(),
+            results: Ok(Results::default()),
+            history: VecDeque::new(),
+            forward_history: Vec::new(),
+            visits: Visits::new(&url),
+            visits_state: ListState::default(),
+        };
+
+        self.remember_selection();
+        let previous = self.swap_tab(tab);
+        self.tabs.insert(self.active_tab, previous);
+        self.active_tab += 1;
+        self.show_cached_page(&url);
+    }
+
+    /// Closes the active tab, switching to the one after it (or the one
+    /// before, if it was the last). Returns false if it's the only tab.
+    pub(super) fn close_tab(&mut self) -> bool {
+        if self.tabs.is_empty() {
+            return false;
+        }
+
+        let index = self.active_tab.min(self.tabs.len() - 1);
+        let next = self.tabs.remove(index);
+        self.swap_tab(next);
+        self.active_tab = index;
+        self.visual_anchor = None;
+        // the filters and sort order may have changed since the tab was left
+        self.refresh_results();
+
+        true
+    }
+
+    /// Switches to the tab at `index` (among all of them), if there's one.
+    pub(super) fn switch_to_tab(&mut self, index: usize) -> bool {
+        if index > self.tabs.len() {
+            return false;
+        }
+        if index == self.active_tab {
+            return true;
+        }
+
+        self.remember_selection();
+        // positions among the inactive tabs, before and after the switch,
+        // such that every tab keeps its number
+        let (target_position, previous_position) = if index > self.active_tab {
+            (index - 1, self.active_tab)
+        } else {
+            (index, self.active_tab - 1)
+        };
+        let target = self.tabs.remove(target_position);
+        let previous = self.swap_tab(target);
+        self.tabs.insert(previous_position, previous);
+        self.active_tab = index;
+        self.visual_anchor = None;
+        // the filters and sort order may have changed since the tab was left
+        self.refresh_results();
+
+        true
+    }
+
+    pub(super) fn tab_count(&self) -> usize {
+        self.tabs.len() + 1
+    }
+
+    /// Returns the details of the page shown in each tab, in order.
+    pub(super) fn tab_pages(&self) -> Vec<&PageDetails> {
+        let mut pages: Vec<&PageDetails> = self.tabs.iter().map(|t| &t.page_details).collect();
+        pages.insert(self.active_tab, &self.page_details);
+        pages
+    }
+
+    // makes `tab` the active one, returning the state of the one it replaces
+    fn swap_tab(&mut self, tab: Tab) -> Tab {
+        Tab {
+            page_details: std::mem::replace(&mut self.page_details, tab.page_details),
+            results: std::mem::replace(&mut self.results, tab.results),
+            history: std::mem::replace(&mut self.history, tab.history),
+            forward_history: std::mem::replace(&mut self.forward_history, tab.forward_history),
+            visits: std::mem::replace(&mut self.visits, tab.visits),
+            visits_state: std::mem::replace(&mut self.visits_state, tab.visits_state),
+        }
+    }
+
+    /// Remembers the selected result of the current page, so that it can be
+    /// restored when returning to the page.
+    pub(super) fn remember_selection(&mut self) {
+        if let Some((url, _)) = self.get_selected_url() {
+            self.selections.insert(self.page_details.url.clone(), url);
+        }
+    }
+
+    /// Rebuilds the results for the current page, keeping the selected URL
+    /// selected if it's still visible.
+    pub(super) fn refresh_results(&mut self) {
+        let selected = self.get_selected_url().map(|(url, _)| url);
+        if self.results.is_ok()
+            && let Some(page) = self.results_cache.get(&self.page_details.url)
+        {
+            let mut results = Results::new(page, &self.filter, &self.fuzzy_filter, self.order);
+            if let Some(index) =
+                selected.and_then(|url| results.items.iter().position(|l| l.url == url))
+            {
+                results.state.select(Some(index));
+            }
+            self.results = Ok(results);
+            self.visual_anchor = None;
+        }
+    }
+}
+
+// ratatui only clamps a list's selection to its items when rendering it; the
+// updates in between need it to point at an item
+fn clamp_selection(state: &mut ListState, len: usize) {
+    if let Some(selected) = state.selected() {
+        state.select(len.checked_sub(1).map(|last| selected.min(last)));
+    }
+}
+
+/// Parses a URL typed in by the user; "https://" is assumed when it has no
+/// scheme.
+pub(super) fn parse_url_input(input: &str) -> Result<String, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err("enter a URL".to_string());
+    }
+
+    let with_scheme = match input.contains("://") {
+        true => input.to_string(),
+        false => format!("https://{}", input),
+    };
+    let url = url::Url::parse(&with_scheme).map_err(|e| e.to_string())?;
+    match url.scheme() {
+        "http" | "https" if url.host_str().is_some() => Ok(url.to_string()),
+        "http" | "https" => Err("missing host".to_string()),
+        scheme => Err(format!("unsupported scheme \"{}\"", scheme)),
+    }
+}
+
+#[cfg(test)]
+impl Model {
+    /// A model for a page holding links to `urls`, in that order.
+    pub(super) fn with_links(urls: &[&str]) -> Self {
+        let links = urls.iter().map(|url| Link::from_url(url)).collect();
+        let config = TuiConfig {
+            filter: UrlFilter::default(),
+            order: LinkOrder::Document,
+            fetch_options: FetchOptions::default(),
+            no_follow_rels: vec![],
+            prefetch: PrefetchOptions::default(),
+        };
+        let dimensions = TerminalDimensions {
+            width: 120,
+            height: 40,
+        };
+
+        Model::new(
+            Page::with_links("https://example.com/", links),
+            config,
+            dimensions,
+            false,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result_urls(model: &Model) -> Vec<&str> {
+        model
+            .results
+            .as_ref()
+            .map(|r| r.items.iter().map(|l| l.url.as_str()).collect())
+            .unwrap_or_default()
+    }
+
+    fn selected(model: &Model) -> Option<usize> {
+        model.results.as_ref().ok()?.state.selected()
+    }
+
+    #[test]
+    fn fuzzy_filter_keeps_the_selected_url_selected() {
+        let mut model = Model::with_links(&[
+            "https://example.com/a",
+            "https://example.com/docs/1",
+            "https://example.com/docs/2",
+        ]);
+        if let Ok(results) = &mut model.results {
+            results.state.select(Some(2));
+        }
+
+        model.fuzzy_filter = "dcs".to_string();
+        model.refresh_results();
+
+        assert_eq!(
+            result_urls(&model),
+            ["https://example.com/docs/1", "https://example.com/docs/2"]
+        );
+        assert_eq!(selected(&model), Some(1));
+    }
+
+    fn search_model() -> Model {
+        let mut model = Model::with_links(&[
+            "https://example.com/a",
+            "https://example.com/docs/1",
+            "https://example.com/b",
+            "https://example.com/docs/2",
+        ]);
+        model.search.set_query("docs");
+        model
+    }
+
+    #[test]
+    fn search_matches_are_selected_in_either_direction() {
+        let mut model = search_model();
+
+        assert!(model.select_search_match(1, true, false));
+        assert_eq!(selected(&model), Some(3));
+        assert!(model.select_search_match(3, false, false));
+        assert_eq!(selected(&model), Some(1));
+        assert!(model.select_search_match(1, true, true));
+        assert_eq!(selected(&model), Some(1));
+    }
+
+    #[test]
+    fn search_matches_wrap_around() {
+        let mut model = search_model();
+
+        assert!(model.select_search_match(3, true, false));
+        assert_eq!(selected(&model), Some(1));
+        assert!(model.select_search_match(0, false, false));
+        assert_eq!(selected(&model), Some(3));
+    }
+
+    #[test]
+    fn selection_goes_back_to_the_origin_without_search_matches() {
+        let mut model = search_model();
+        model.search_origin = Some(2);
+
+        model.update_search_selection();
+        assert_eq!(selected(&model), Some(3));
+
+        model.search.set_query("nothing");
+        assert!(!model.has_search_matches());
+        model.update_search_selection();
+        assert_eq!(selected(&model), Some(2));
+    }
+
+    // a model with the pages "a" and "b" cached, each in a tab of its own after
+    // the first page's one; the tab showing "a" is active
+    fn tabs_model() -> Model {
+        let mut model = Model::with_links(&["https://a.com/", "https://b.com/"]);
+        for url in ["https://a.com/", "https://b.com/"] {
+            let page = Page::with_links(url, vec![Link::from_url("https://z.com/")]);
+            model.results_cache.insert(url.to_string(), page);
+        }
+
+        model.open_tab();
+        model.navigate_to("https://a.com/");
+        model.open_tab();
+        model.navigate_to("https://b.com/");
+        model.switch_to_tab(1);
+        model
+    }
+
+    fn tab_urls(model: &Model) -> Vec<&str> {
+        model
+            .tab_pages()
+            .iter()
+            .map(|page| page.url.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn tabs_are_opened_after_the_active_one() {
+        let mut model = tabs_model();
+
+        model.open_tab();
+
+        assert_eq!(model.active_tab, 2);
+        assert_eq!(
+            tab_urls(&model),
+            [
+                "https://example.com/",
+                "https://a.com/",
+                "https://a.com/",
+                "https://b.com/"
+            ]
+        );
+    }
+
+    #[test]
+    fn tabs_keep_their_number_when_switching() {
+        let mut model = tabs_model();
+        let urls = ["https://example.com/", "https://a.com/", "https://b.com/"];
+
+        assert!(model.switch_to_tab(0));
+        assert_eq!(tab_urls(&model), urls);
+        assert_eq!(model.page_details.url, "https://example.com/");
+
+        assert!(model.switch_to_tab(2));
+        assert_eq!(tab_urls(&model), urls);
+        assert_eq!(model.page_details.url, "https://b.com/");
+
+        assert!(!model.switch_to_tab(3));
+        assert_eq!(model.active_tab, 2);
+    }
+
+    #[test]
+    fn tabs_have_a_history_of_their_own() {
+        let mut model = tabs_model();
+        assert_eq!(model.history, ["https://example.com/"]);
+
+        model.switch_to_tab(2);
+        assert_eq!(model.history, ["https://a.com/"]);
+
+        model.switch_to_tab(0);
+        assert!(model.history.is_empty());
+    }
+
+    #[test]
+    fn tabs_are_shown_with_the_current_filters() {
+        let mut model = tabs_model();
+
+        model.fuzzy_filter = "a.com".to_string();
+        model.switch_to_tab(0);
+
+        assert_eq!(result_urls(&model), ["https://a.com/"]);
+    }
+
+    #[test]
+    fn closing_a_tab_switches_to_the_next_one_or_the_last() {
+        let mut model = tabs_model();
+
+        assert!(model.close_tab());
+        assert_eq!(model.active_tab, 1);
+        assert_eq!(model.page_details.url, "https://b.com/");
+
+        assert!(model.close_tab());
+        assert_eq!(model.active_tab, 0);
+        assert_eq!(model.page_details.url, "https://example.com/");
+
+        assert!(!model.close_tab());
+        assert_eq!(model.tab_count(), 1);
+    }
+
+    #[test]
+    fn typed_urls_default_to_https() {
+        assert_eq!(
+            parse_url_input(" example.com/a ").as_deref(),
+            Ok("https://example.com/a")
+        );
+        assert_eq!(
+            parse_url_input("http://example.com").as_deref(),
+            Ok("http://example.com/")
+        );
+        assert!(parse_url_input("").is_err());
+        assert!(parse_url_input("ftp://example.com").is_err());
+        assert!(parse_url_input("https://").is_err());
+    }
+
+    #[test]
+    fn completions_are_cycled_through_in_either_direction() {
+        let candidates = |_: &str| vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        let mut prompt = Prompt::new(PromptKind::OpenUrl, String::new());
+
+        assert!(prompt.complete(false, candidates));
+        assert_eq!(prompt.input, "c");
+        assert!(prompt.complete(true, candidates));
+        assert_eq!(prompt.input, "a");
+        assert!(prompt.complete(false, candidates));
+        assert_eq!(prompt.input, "c");
+
+        let mut prompt = Prompt::new(PromptKind::OpenUrl, String::new());
+        assert!(!prompt.complete(true, |_| vec![]));
+    }
+
+    #[test]
+    fn url_completions_put_recently_visited_pages_first() {
+        let mut model = tabs_model();
+        model.switch_to_tab(2);
+
+        assert_eq!(model.url_completions("b."), ["https://b.com/"]);
+        assert_eq!(
+            model.url_completions("https://"),
+            ["https://a.com/", "https://b.com/", "https://example.com/"]
+        );
+        assert!(model.url_completions("nothing").is_empty());
+    }
 }
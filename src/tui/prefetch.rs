@@ -0,0 +1,81 @@
+pub const DEFAULT_PREFETCH_NEIGHBORS: usize = 2;
+pub const DEFAULT_PREFETCH_CONCURRENCY: usize = 4;
+pub const DEFAULT_PREFETCH_PER_HOST: usize = 2;
+pub const MAX_PREFETCH_NEIGHBORS: usize = 50;
+
+/// Controls fetching the pages of links near the cursor in the background, so
+/// that they're cached by the time they're chosen.
+#[derive(Debug, Clone, Copy)]
+pub struct PrefetchOptions {
+    pub enabled: bool,
+    /// number of links above and below the selected one to prefetch
+    pub neighbors: usize,
+    /// maximum number of pages to prefetch at once
+    pub concurrency: usize,
+    /// maximum number of pages to prefetch at once from a single host
+    pub per_host: usize,
+}
+
+impl Default for PrefetchOptions {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            neighbors: DEFAULT_PREFETCH_NEIGHBORS,
+            concurrency: DEFAULT_PREFETCH_CONCURRENCY,
+            per_host: DEFAULT_PREFETCH_PER_HOST,
+        }
+    }
+}
+
+/// Returns the indices (below `len`) of the links to prefetch: the selected
+/// one first, then its neighbors, nearest first.
+pub(super) fn candidate_indices(selected: usize, neighbors: usize, len: usize) -> Vec<usize> {
+    let mut indices = Vec::new();
+    if selected < len {
+        indices.push(selected);
+    }
+    for distance in 1..=neighbors.min(len) {
+        if let Some(index) = selected.checked_add(distance).filter(|i| *i < len) {
+            indices.push(index);
+        }
+        if let Some(index) = selected.checked_sub(distance) {
+            indices.push(index);
+        }
+    }
+
+    indices
+}
+
+pub(super) fn host(url: &str) -> Option<String> {
+    url::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| h.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn candidates_are_the_selected_link_then_the_nearest_neighbors() {
+        assert_eq!(candidate_indices(5, 2, 10), [5, 6, 4, 7, 3]);
+        assert_eq!(candidate_indices(5, 0, 10), [5]);
+    }
+
+    #[test]
+    fn candidates_stop_at_either_end_of_the_list() {
+        assert_eq!(candidate_indices(1, 3, 10), [1, 2, 0, 3, 4]);
+        assert_eq!(candidate_indices(1, 3, 3), [1, 2, 0]);
+        assert_eq!(candidate_indices(0, usize::MAX, 2), [0, 1]);
+        assert!(candidate_indices(0, 2, 0).is_empty());
+    }
+
+    #[test]
+    fn hosts_are_parsed_from_urls() {
+        assert_eq!(
+            host("https://docs.example.com/a?b").as_deref(),
+            Some("docs.example.com")
+        );
+        assert_eq!(host("not a url"), None);
+    }
+}
@@ -0,0 +1,205 @@
+use crate::domain::Link;
+use regex::{Regex, RegexBuilder};
+
+/// Search over the results list. Matching is done against URLs as they're
+/// displayed (ie, normalized or raw).
+#[derive(Debug, Default)]
+pub(crate) struct Search {
+    pub(crate) query: String,
+    pub(crate) case_sensitive: bool,
+    pub(crate) regex: bool,
+    /// compiled from the fields above; absent when the query is empty or isn't
+    /// a valid regex
+    matcher: Option<Regex>,
+}
+
+impl Search {
+    pub(super) fn set_query(&mut self, query: &str) {
+        self.query = query.to_string();
+        self.compile();
+    }
+
+    pub(super) fn toggle_case_sensitivity(&mut self) {
+        self.case_sensitive = !self.case_sensitive;
+        self.compile();
+    }
+
+    pub(super) fn toggle_regex(&mut self) {
+        self.regex = !self.regex;
+        self.compile();
+    }
+
+    pub(super) fn clear(&mut self) {
+        self.set_query("");
+    }
+
+    pub(crate) fn is_active(&self) -> bool {
+        !self.query.is_empty()
+    }
+
+    /// Returns an error when the query is meant to be a regex, but isn't valid.
+    pub(super) fn error(&self) -> Option<String> {
+        if !self.is_active() || self.matcher.is_some() {
+            return None;
+        }
+
+        self.build_matcher().err().map(|e| {
+            e.to_string()
+                .lines()
+                .last()
+                .unwrap_or_default()
+                .trim_start_matches("error: ")
+                .to_string()
+        })
+    }
+
+    pub(crate) fn matcher(&self) -> Option<&Regex> {
+        self.matcher.as_ref()
+    }
+
+    pub(super) fn matches(&self, link: &Link, show_raw_urls: bool) -> bool {
+        self.matcher
+            .as_ref()
+            .is_some_and(|m| m.is_match(displayed_url(link, show_raw_urls)))
+    }
+
+    fn compile(&mut self) {
+        self.matcher = match self.is_active() {
+            true => self.build_matcher().ok(),
+            false => None,
+        };
+    }
+
+    fn build_matcher(&self) -> Result<Regex, regex::Error> {
+        let pattern = match self.regex {
+            true => self.query.clone(),
+            false => regex::escape(&self.query),
+        };
+
+        RegexBuilder::new(&pattern)
+            .case_insensitive(!self.case_sensitive)
+            .build()
+    }
+}
+
+/// Whether a link matches a fuzzy query: every whitespace separated term of the
+/// query has to appear, in order but not necessarily contiguously, in either
+/// the link's URL or its text. Case is ignored.
+pub(crate) fn fuzzy_matches(query: &str, link: &Link) -> bool {
+    query.split_whitespace().all(|term| {
+        is_subsequence(term, &link.url)
+            || link
+                .text
+                .as_deref()
+                .is_some_and(|text| is_subsequence(term, text))
+    })
+}
+
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut haystack = haystack.chars().flat_map(char::to_lowercase);
+    needle
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|c| haystack.any(|h| h == c))
+}
+
+pub(crate) fn displayed_url(link: &Link, show_raw_urls: bool) -> &str {
+    match show_raw_urls {
+        true => link.raw.as_str(),
+        false => link.url.as_str(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn search(query: &str) -> Search {
+        let mut search = Search::default();
+        search.set_query(query);
+        search
+    }
+
+    fn link(url: &str, raw: &str) -> Link {
+        Link {
+            raw: raw.to_string(),
+            ..Link::from_url(url)
+        }
+    }
+
+    #[test]
+    fn queries_are_literal_and_case_insensitive_by_default() {
+        let search = search("A.B");
+
+        assert!(search.matches(&Link::from_url("https://a.b/"), false));
+        assert!(!search.matches(&Link::from_url("https://axb/"), false));
+        assert!(search.error().is_none());
+    }
+
+    #[test]
+    fn case_sensitivity_can_be_toggled() {
+        let mut search = search("Docs");
+        search.toggle_case_sensitivity();
+
+        assert!(!search.matches(&Link::from_url("https://example.com/docs"), false));
+        assert!(search.matches(&Link::from_url("https://example.com/Docs"), false));
+    }
+
+    #[test]
+    fn queries_can_be_regexes() {
+        let mut search = search("v[0-9]+$");
+        assert!(!search.matches(&Link::from_url("https://example.com/v2"), false));
+
+        search.toggle_regex();
+        assert!(search.matches(&Link::from_url("https://example.com/v2"), false));
+        assert!(!search.matches(&Link::from_url("https://example.com/v2/a"), false));
+    }
+
+    #[test]
+    fn invalid_regexes_match_nothing_and_report_an_error() {
+        let mut search = search("(unclosed");
+        search.toggle_regex();
+
+        assert!(!search.matches(&Link::from_url("https://example.com/(unclosed"), false));
+        assert!(search.error().is_some());
+    }
+
+    #[test]
+    fn empty_queries_match_nothing() {
+        let mut search = search("docs");
+        search.clear();
+
+        assert!(!search.is_active());
+        assert!(!search.matches(&Link::from_url("https://example.com/docs"), false));
+    }
+
+    #[test]
+    fn fuzzy_terms_match_urls_or_text_in_order() {
+        let link = Link {
+            text: Some("Getting Started".to_string()),
+            ..Link::from_url("https://example.com/docs/install")
+        };
+
+        assert!(fuzzy_matches("", &link));
+        assert!(fuzzy_matches("dcinst", &link));
+        assert!(fuzzy_matches("GS docs", &link));
+        assert!(!fuzzy_matches("tsnid", &link));
+        assert!(!fuzzy_matches("docs zzz", &link));
+    }
+
+    #[test]
+    fn subsequences_ignore_case() {
+        assert!(is_subsequence("ÉCL", "école"));
+        assert!(is_subsequence("", "anything"));
+        assert!(!is_subsequence("ab", "ba"));
+    }
+
+    #[test]
+    fn urls_are_matched_as_displayed() {
+        let search = search("utm_source");
+        let link = link("https://example.com/", "https://example.com/?utm_source=x");
+
+        assert!(!search.matches(&link, false));
+        assert!(search.matches(&link, true));
+    }
+}
@@ -1,51 +1,164 @@
+use super::cmdline::{self, Setting};
 use super::command::Command;
 use super::common::*;
 use super::message::Message;
 use super::model::*;
+use super::prefetch;
+use crate::args::parse_filter;
+use crate::domain::{LinkKind, UrlFilter};
 
 pub fn update(model: &mut Model, msg: Message) -> Vec<Command> {
+    let mut cmds = handle_message(model, msg);
+
+    if model.miller_columns {
+        cmds.extend(preview_selected_link(model));
+    }
+    if model.prefetch.enabled || !model.crawl_queue.is_empty() {
+        cmds.extend(fetch_in_background(model));
+    }
+
+    if let Some(message) = &mut model.user_message {
+        let clear = if message.frames_left == 0 {
+            true
+        } else {
+            message.frames_left -= 1;
+            false
+        };
+
+        if clear {
+            model.user_message = None;
+        }
+    }
+
+    cmds
+}
+
+// the part of an update specific to the message; commands entered on the
+// command line are handled through here as well
+fn handle_message(model: &mut Model, msg: Message) -> Vec<Command> {
     let mut cmds = Vec::new();
 
     match msg {
         Message::UrlChosen => {
-            if let Some((url, _)) = model.get_selected_url() {
+            if let Some(rel) = model.selected_link_no_follow_rel() {
+                model.user_message = Some(UserMessage::error(&format!(
+                    "not following link marked rel=\"{}\"",
+                    rel
+                )));
+            } else if let Some((url, _)) = model.get_selected_url() {
+                cmds.extend(go_to_url(model, url));
+            }
+        }
+        Message::OpenTab => {
+            if let Some(rel) = model.selected_link_no_follow_rel() {
+                model.user_message = Some(UserMessage::error(&format!(
+                    "not following link marked rel=\"{}\"",
+                    rel
+                )));
+            } else if let Some((url, _)) = model.get_selected_url() {
                 if url == model.page_details.url {
                     model.user_message = Some(UserMessage::error(
                         "selected URL is the same as the current one",
                     ));
-                } else if let Some(page) = model.results_cache.get(&url) {
-                    model.history.push_back(model.page_details.url.clone());
-                    model.page_details.url = url;
-                    model.page_details = page.details.clone();
-                    model.results = Ok(Results::from(page));
                 } else {
-                    cmds.push(Command::GetResults {
-                        current: model.page_details.url.clone(),
-                        chosen: url,
-                    });
+                    model.open_tab();
+                    cmds.extend(go_to_url(model, url));
                 }
             }
         }
+        Message::CloseTab => {
+            if model.close_tab() {
+                model.loading = None;
+                model.user_message = None;
+            } else {
+                model.user_message = Some(UserMessage::error("can't close the only tab"));
+            }
+        }
+        Message::GoToNextTab | Message::GoToPreviousTab => {
+            let count = model.tab_count();
+            let index = match msg {
+                Message::GoToNextTab => (model.active_tab + 1) % count,
+                _ => (model.active_tab + count - 1) % count,
+            };
+            switch_to_tab(model, index);
+        }
+        Message::GoToTab(index) => switch_to_tab(model, index),
         Message::ResultsFetched {
-            previous,
+            request_id,
             page_result,
-        } => match page_result {
-            Ok(page) => {
-                if page.page_urls.is_empty() {
+        } => match (
+            model.take_background_fetch(|l| l.id == request_id),
+            page_result,
+        ) {
+            (Some(fetch), Ok(page)) => {
+                if page.links.is_empty() {
+                    model
+                        .background_failures
+                        .insert(fetch.url, "no urls on the page".to_string());
+                } else {
+                    model.results_cache.insert(page.details.url.clone(), *page);
+                }
+            }
+            (Some(fetch), Err(e)) => {
+                model.background_failures.insert(fetch.url, e);
+            }
+            // the user has moved on since the fetch started; its results are
+            // kept around in case they're needed later
+            (None, Ok(page)) if !model.is_awaiting(request_id) => {
+                if !page.links.is_empty() {
+                    model.results_cache.insert(page.details.url.clone(), *page);
+                }
+            }
+            (None, Err(_)) if !model.is_awaiting(request_id) => {}
+            // the current page, refreshed
+            (None, Ok(page)) if page.details.url == model.page_details.url => {
+                model.loading = None;
+                let url = page.details.url.clone();
+                model.remember_selection();
+                model.results_cache.insert(url.clone(), *page);
+                model.show_cached_page(&url);
+                model.user_message = Some(UserMessage::info("refreshed"));
+            }
+            (None, Ok(page)) => {
+                model.loading = None;
+                if page.links.is_empty() {
                     model.user_message = Some(UserMessage::info("no urls on the selected page"));
                 } else {
-                    model.results = Ok(Results::from(&page.page_urls));
-                    model.page_details = page.details.clone();
-                    model.results_cache.insert(page.details.url.clone(), page);
-                    model.history.push_back(previous.clone());
+                    let url = page.details.url.clone();
+                    model.results_cache.insert(url.clone(), *page);
+                    model.navigate_to(&url);
                     model.user_message = None;
                 }
             }
-            Err(e) => {
+            (None, Err(e)) => {
+                model.loading = None;
                 model.results = Err(e);
-                model.history.push_back(previous.clone());
+                model.history.push_back(model.page_details.url.clone());
             }
         },
+        Message::FetchTimedOut {
+            request_id,
+            url,
+            error,
+        } => {
+            if model
+                .take_background_fetch(|l| l.id == request_id)
+                .is_some()
+            {
+                model.background_failures.insert(url, error);
+            } else if model.is_awaiting(request_id) {
+                model.loading = None;
+                model.user_message = Some(
+                    UserMessage::error(&format!("fetching {} {}", url, error)).with_frames_left(8),
+                );
+            }
+        }
+        Message::CancelFetch => {
+            if let Some(loading) = model.loading.take() {
+                loading.cancel();
+                model.user_message = Some(UserMessage::info("cancelled"));
+            }
+        }
         Message::GoToNextListItem => model.select_next_list_item(),
         Message::GoToPreviousListItem => model.select_previous_list_item(),
         Message::GoToFirstListItem => model.select_first_list_item(),
@@ -56,13 +169,20 @@ pub fn update(model: &mut Model, msg: Message) -> Vec<Command> {
                 !(width >= MIN_TERMINAL_WIDTH && height >= MIN_TERMINAL_HEIGHT);
         }
         Message::YankUrlToClipboard => {
-            if let Some((url, _)) = model.get_selected_url() {
+            if !model.marks.is_empty() {
+                cmds.push(Command::YankContentToClipboard(model.marks.join("\n")));
+            } else if let Some((url, _)) = model.get_selected_url() {
                 cmds.push(Command::YankContentToClipboard(url));
             }
         }
         Message::YankUrlsToClipboard => {
             if let Ok(results) = &model.results {
-                let urls = results.items.join("\n");
+                let urls = results
+                    .items
+                    .iter()
+                    .map(|link| link.url.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n");
                 cmds.push(Command::YankContentToClipboard(urls));
             }
         }
@@ -77,10 +197,78 @@ pub fn update(model: &mut Model, msg: Message) -> Vec<Command> {
             }
         }
         Message::OpenUrlInBrowser => {
-            if let Some((url, _)) = model.get_selected_url() {
+            if !model.marks.is_empty() {
+                cmds.extend(model.marks.iter().cloned().map(Command::OpenInBrowser));
+            } else if let Some((url, _)) = model.get_selected_url() {
                 cmds.push(Command::OpenInBrowser(url));
             }
         }
+        Message::ToggleMark => {
+            if let Some((url, _)) = model.get_selected_url() {
+                model.toggle_mark(&url);
+                model.select_next_list_item();
+            }
+        }
+        Message::ToggleVisualMarking => match model.visual_range() {
+            Some(range) => {
+                let urls = model.visible_urls();
+                for url in &urls[range] {
+                    model.mark(url);
+                }
+                model.visual_anchor = None;
+            }
+            None => model.visual_anchor = model.get_selected_url().map(|(_, index)| index),
+        },
+        Message::CancelVisualMarking => model.visual_anchor = None,
+        Message::InvertMarks => {
+            for url in model.visible_urls() {
+                model.toggle_mark(&url);
+            }
+        }
+        Message::MarkAllVisible => {
+            for url in model.visible_urls() {
+                model.mark(&url);
+            }
+        }
+        Message::ClearMarks => {
+            model.marks.clear();
+            model.visual_anchor = None;
+        }
+        Message::ExportMarks => {
+            if model.marks.is_empty() {
+                model.user_message = Some(UserMessage::error("no urls are marked"));
+            } else {
+                let mut contents = model.marks.join("\n");
+                contents.push('\n');
+                cmds.push(Command::WriteToFile {
+                    path: MARKS_FILE.to_string(),
+                    contents,
+                    append: false,
+                });
+            }
+        }
+        Message::CrawlMarks => {
+            let mut crawled = 0;
+            for url in &model.marks {
+                if model.results_cache.contains_key(url) || model.crawl_queue.contains(url) {
+                    continue;
+                }
+                // prefetches become part of the crawl, so that turning
+                // prefetching off doesn't cancel them
+                if let Some(index) = model.prefetches.iter().position(|p| &p.url == url) {
+                    let fetch = model.prefetches.swap_remove(index);
+                    model.crawls.push(fetch);
+                    crawled += 1;
+                } else if !model.is_being_fetched(url) {
+                    model.crawl_queue.push_back(url.clone());
+                    crawled += 1;
+                }
+            }
+            model.user_message = Some(UserMessage::info(&format!(
+                "crawling {} marked urls",
+                crawled
+            )));
+        }
         Message::UrlOpened(result) => {
             if let Err(error) = result {
                 model.user_message = Some(
@@ -92,13 +280,310 @@ pub fn update(model: &mut Model, msg: Message) -> Vec<Command> {
         Message::GoToPane(pane) => {
             model.last_active_pane = Some(model.active_pane);
             model.active_pane = pane;
+            model.pane_scroll = 0;
+            match pane {
+                Pane::Source => {
+                    model.source_match = None;
+                    model.locate_selected_link_in_source();
+                }
+                Pane::History => {
+                    let current = model.visits.current();
+                    let row = model.visits.rows().iter().position(|r| r.index == current);
+                    model.visits_state.select(row);
+                }
+                _ => {}
+            }
+        }
+        Message::VisitChosen => {
+            let index = model
+                .visits_state
+                .selected()
+                .and_then(|row| model.visits.rows().get(row).map(|r| r.index));
+            if let Some(index) = index {
+                if model.jump_to_visit(index) {
+                    // a page being fetched is no longer wanted
+                    model.loading = None;
+                    model.active_pane = Pane::ResultsList;
+                    model.last_active_pane = Some(Pane::History);
+                    model.user_message = None;
+                } else {
+                    model.user_message = Some(UserMessage::error("page is no longer cached"));
+                }
+            }
+        }
+        Message::ExportVisitsAsMarkdown => cmds.push(Command::WriteToFile {
+            path: HISTORY_MARKDOWN_FILE.to_string(),
+            contents: model.visits.to_markdown(&model.results_cache),
+            append: false,
+        }),
+        Message::ExportVisitsAsJson => {
+            match serde_json::to_string_pretty(&model.visits.to_json(&model.results_cache)) {
+                Ok(contents) => cmds.push(Command::WriteToFile {
+                    path: HISTORY_JSON_FILE.to_string(),
+                    contents,
+                    append: false,
+                }),
+                Err(e) => {
+                    model.user_message = Some(UserMessage::error(&format!(
+                        "couldn't serialize history: {}",
+                        e
+                    )));
+                }
+            }
+        }
+        Message::FileWritten { path, result } => {
+            model.user_message = Some(match result {
+                Ok(()) => UserMessage::info(&format!("wrote {}", path)),
+                Err(error) => UserMessage::error(&format!("couldn't write {}: {}", path, error))
+                    .with_frames_left(8),
+            });
+        }
+        Message::OpenPrompt(kind) => {
+            let input = match kind {
+                PromptKind::Filter => model.filter.to_string(),
+                PromptKind::ReaderLink => String::new(),
+                PromptKind::SourceSearch => model.source_search.clone().unwrap_or_default(),
+                PromptKind::FuzzyFilter => model.fuzzy_filter.clone(),
+                PromptKind::OpenUrl | PromptKind::Command => String::new(),
+                PromptKind::Search => {
+                    model.search_origin = model.get_selected_url().map(|(_, index)| index);
+                    model.search.query.clone()
+                }
+            };
+            model.prompt = Some(Prompt::new(kind, input));
+        }
+        Message::OpenPromptWithInput(kind, input) => {
+            model.prompt = Some(Prompt::new(kind, input));
+        }
+        Message::PromptInput(c) => {
+            if let Some(prompt) = &mut model.prompt {
+                prompt.input.push(c);
+                prompt.completion = None;
+                match prompt.kind {
+                    PromptKind::Search => {
+                        model.search.set_query(&prompt.input);
+                        model.update_search_selection();
+                    }
+                    PromptKind::FuzzyFilter => {
+                        model.fuzzy_filter = prompt.input.clone();
+                        model.refresh_results();
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Message::PromptBackspace => {
+            if let Some(prompt) = &mut model.prompt {
+                prompt.input.pop();
+                prompt.completion = None;
+                match prompt.kind {
+                    PromptKind::Search => {
+                        model.search.set_query(&prompt.input);
+                        model.update_search_selection();
+                    }
+                    PromptKind::FuzzyFilter => {
+                        model.fuzzy_filter = prompt.input.clone();
+                        model.refresh_results();
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Message::CompletePrompt | Message::CompletePromptBackwards => {
+            if let Some(mut prompt) = model.prompt.take() {
+                let forward = matches!(msg, Message::CompletePrompt);
+                let completed = match prompt.kind {
+                    PromptKind::OpenUrl => {
+                        prompt.complete(forward, |input| model.url_completions(input))
+                    }
+                    PromptKind::Command => prompt.complete(forward, |input| {
+                        cmdline::command_completions(input, |url| model.url_completions(url))
+                    }),
+                    // nothing to complete in other prompts
+                    _ => true,
+                };
+                if !completed {
+                    model.user_message = Some(UserMessage::error("no completions"));
+                }
+                model.prompt = Some(prompt);
+            }
+        }
+        Message::SubmitPrompt => {
+            if let Some(prompt) = model.prompt.take() {
+                match prompt.kind {
+                    PromptKind::Filter => apply_filter(model, &prompt.input),
+                    // already applied while typing
+                    PromptKind::FuzzyFilter => {}
+                    PromptKind::Search => {
+                        model.search_origin = None;
+                        model.search.set_query(&prompt.input);
+                        if let Some(error) = model.search.error() {
+                            model.user_message =
+                                Some(UserMessage::error(&format!("invalid regex: {}", error)));
+                        } else if model.search.is_active() && !model.has_search_matches() {
+                            model.user_message = Some(UserMessage::error("no matches"));
+                        }
+                    }
+                    PromptKind::SourceSearch => {
+                        model.source_search = Some(prompt.input).filter(|q| !q.is_empty());
+                        model.source_match = None;
+                        model.go_to_source_search_match(true);
+                    }
+                    PromptKind::OpenUrl => match parse_url_input(&prompt.input) {
+                        Ok(url) => cmds.extend(go_to_url(model, url)),
+                        Err(e) => {
+                            model.user_message =
+                                Some(UserMessage::error(&format!("invalid url: {}", e)));
+                        }
+                    },
+                    PromptKind::Command => match cmdline::parse_command(&prompt.input) {
+                        Ok(msg) => cmds.extend(handle_message(model, msg)),
+                        Err(e) => model.user_message = Some(UserMessage::error(&e)),
+                    },
+                    PromptKind::ReaderLink => {
+                        let url = prompt.input.trim().parse::<usize>().ok().and_then(|n| {
+                            model
+                                .results_cache
+                                .get(&model.page_details.url)
+                                .and_then(|page| page.reader.link(n))
+                                .map(|url| url.to_string())
+                        });
+                        match url {
+                            Some(url) => cmds.extend(go_to_url(model, url)),
+                            None => {
+                                model.user_message = Some(UserMessage::error(&format!(
+                                    "no link numbered \"{}\"",
+                                    prompt.input.trim()
+                                )));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Message::CancelPrompt => match model.prompt.take() {
+            Some(prompt) if prompt.kind == PromptKind::Search => {
+                model.search.clear();
+                if let (Some(origin), Ok(results)) =
+                    (model.search_origin.take(), &mut model.results)
+                {
+                    results.state.select(Some(origin));
+                }
+            }
+            Some(prompt) if prompt.kind == PromptKind::FuzzyFilter => {
+                model.fuzzy_filter = prompt.initial_input;
+                model.refresh_results();
+            }
+            _ => {}
+        },
+        Message::ClearFilter => {
+            if !model.filter.is_empty() || !model.fuzzy_filter.is_empty() {
+                model.filter = UrlFilter::default();
+                model.fuzzy_filter.clear();
+                model.refresh_results();
+                model.user_message = Some(UserMessage::info("filter cleared"));
+            }
+        }
+        Message::ToggleRawUrls => model.show_raw_urls = !model.show_raw_urls,
+        Message::ToggleMillerColumns => {
+            model.miller_columns = !model.miller_columns;
+            if !model.miller_columns
+                && let Some(preview) = model.preview.take()
+            {
+                preview.cancel();
+            }
+        }
+        Message::TogglePrefetch => {
+            model.prefetch.enabled = !model.prefetch.enabled;
+            if model.prefetch.enabled {
+                model.user_message = Some(UserMessage::info("prefetching enabled"));
+            } else {
+                for prefetch in model.prefetches.drain(..) {
+                    prefetch.cancel();
+                }
+                model.user_message = Some(UserMessage::info("prefetching disabled"));
+            }
+        }
+        Message::CycleSortOrder | Message::SetSortOrder(_) => {
+            model.order = match msg {
+                Message::SetSortOrder(order) => order,
+                _ => model.order.next(),
+            };
+            model.refresh_results();
+            model.user_message = Some(UserMessage::info(&format!("sorted by {}", model.order)));
+        }
+        Message::Set(setting, value) => {
+            let (enabled, toggle) = match setting {
+                Setting::RawUrls => (model.show_raw_urls, Message::ToggleRawUrls),
+                Setting::MillerColumns => (model.miller_columns, Message::ToggleMillerColumns),
+                Setting::Prefetch => (model.prefetch.enabled, Message::TogglePrefetch),
+            };
+            if value.unwrap_or(!enabled) != enabled {
+                cmds.extend(handle_message(model, toggle));
+            }
+        }
+        Message::OpenUrl(url) => cmds.extend(go_to_url(model, url)),
+        Message::ApplyFilter(spec) => apply_filter(model, &spec),
+        Message::Refresh => {
+            if model.results.is_err() {
+                model.user_message = Some(UserMessage::error("there's no page to refresh"));
+            } else {
+                let url = model.page_details.url.clone();
+                model.background_failures.remove(&url);
+                let (loading, cancel) = model.start_loading(&url);
+                cmds.push(Command::GetResults {
+                    request_id: loading.id,
+                    url,
+                    options: model.fetch_options.clone(),
+                    cancel,
+                });
+                model.loading = Some(loading);
+            }
+        }
+        Message::Bookmark => cmds.push(Command::WriteToFile {
+            path: BOOKMARKS_FILE.to_string(),
+            contents: format!("{}\n", model.page_details.url),
+            append: true,
+        }),
+        Message::GoToNextSearchMatch | Message::GoToPreviousSearchMatch => {
+            let forward = matches!(msg, Message::GoToNextSearchMatch);
+            match model.active_pane {
+                Pane::Source => model.go_to_source_search_match(forward),
+                _ => {
+                    if let Some((_, index)) = model.get_selected_url()
+                        && model.search.is_active()
+                        && !model.select_search_match(index, forward, false)
+                    {
+                        model.user_message = Some(UserMessage::error("no matches"));
+                    }
+                }
+            }
+        }
+        Message::ToggleSearchCaseSensitivity | Message::ToggleSearchRegex => {
+            if model
+                .prompt
+                .as_ref()
+                .is_some_and(|p| p.kind == PromptKind::Search)
+            {
+                match msg {
+                    Message::ToggleSearchRegex => model.search.toggle_regex(),
+                    _ => model.search.toggle_case_sensitivity(),
+                }
+                model.update_search_selection();
+            }
         }
         Message::GoBack => {
             if let Some(last_url) = model.history.pop_back() {
-                if let Some(page) = model.results_cache.get(&last_url) {
-                    model.page_details.url = last_url;
-                    model.page_details = page.details.clone();
-                    model.results = Ok(Results::from(page));
+                // when showing an error, the current page is the one being
+                // returned to
+                if model.results.is_ok() {
+                    model.remember_selection();
+                    model.forward_history.push(model.page_details.url.clone());
+                    model.visits.go_to_parent();
+                }
+                if model.show_cached_page(&last_url) {
+                    // a page being fetched is no longer wanted
+                    model.loading = None;
                     model.user_message = None;
                 } else {
                     model.user_message = Some(UserMessage::error("something went wrong"));
@@ -107,22 +592,487 @@ pub fn update(model: &mut Model, msg: Message) -> Vec<Command> {
                 model.user_message = Some(UserMessage::error("at the start of navigation history"));
             }
         }
+        Message::GoForward => {
+            if let Some(next_url) = model.forward_history.pop() {
+                // when showing an error, the current page was pushed already
+                if model.results.is_ok() {
+                    model.remember_selection();
+                    model.history.push_back(model.page_details.url.clone());
+                }
+                model.visits.visit(&next_url);
+                if model.show_cached_page(&next_url) {
+                    model.loading = None;
+                    model.user_message = None;
+                } else {
+                    model.user_message = Some(UserMessage::error("something went wrong"));
+                }
+            } else {
+                model.user_message = Some(UserMessage::error("at the end of navigation history"));
+            }
+        }
         Message::GoBackOrQuit => model.go_back_or_quit(),
         Message::QuitImmediately => model.running_state = RunningState::Done,
     }
 
-    if let Some(message) = &mut model.user_message {
-        let clear = if message.frames_left == 0 {
-            true
-        } else {
-            message.frames_left -= 1;
-            false
-        };
+    cmds
+}
 
-        if clear {
-            model.user_message = None;
+fn apply_filter(model: &mut Model, spec: &str) {
+    match parse_filter(spec) {
+        Ok(filter) => {
+            model.filter = filter;
+            model.refresh_results();
+            model.user_message = Some(if model.filter.is_empty() {
+                UserMessage::info("filter cleared")
+            } else {
+                UserMessage::info("filter applied")
+            });
+        }
+        Err(e) => {
+            model.user_message = Some(UserMessage::error(&format!("invalid filter: {}", e)));
+        }
+    }
+}
+
+fn switch_to_tab(model: &mut Model, index: usize) {
+    // a page being fetched in the tab is still awaited
+    if index == model.active_tab {
+        return;
+    }
+
+    if model.switch_to_tab(index) {
+        // a page being fetched in the tab left is no longer awaited; it'll
+        // still be cached
+        model.loading = None;
+        model.user_message = None;
+    } else {
+        model.user_message = Some(UserMessage::error(&format!("there's no tab {}", index + 1)));
+    }
+}
+
+/// Shows the page at `url`, from the cache if it was fetched before; otherwise
+/// returns the command to fetch it.
+fn go_to_url(model: &mut Model, url: String) -> Option<Command> {
+    if url == model.page_details.url {
+        model.user_message = Some(UserMessage::error(
+            "selected URL is the same as the current one",
+        ));
+        return None;
+    }
+
+    if model.results_cache.contains_key(&url) {
+        model.loading = None;
+        model.navigate_to(&url);
+        return None;
+    }
+
+    // the page is already being fetched in the background; its results are
+    // awaited instead of starting over
+    if let Some(fetch) = model.take_background_fetch(|l| l.url == url) {
+        model.loading = Some(fetch);
+        return None;
+    }
+
+    // replacing an earlier fetch doesn't cancel it; its results will still be
+    // cached
+    let (loading, cancel) = model.start_loading(&url);
+    let request_id = loading.id;
+    model.loading = Some(loading);
+    Some(Command::GetResults {
+        request_id,
+        url,
+        options: model.fetch_options.clone(),
+        cancel,
+    })
+}
+
+/// Returns the command to fetch the selected link's page for previewing, if it
+/// isn't cached or being fetched already. Any earlier preview fetch is
+/// cancelled, so that scrolling through the results doesn't pile them up.
+fn preview_selected_link(model: &mut Model) -> Option<Command> {
+    let link = model.get_selected_link()?;
+    if matches!(link.kind, LinkKind::Fragment | LinkKind::Download)
+        || model.selected_link_no_follow_rel().is_some()
+    {
+        return None;
+    }
+
+    let url = link.url.clone();
+    if model.results_cache.contains_key(&url)
+        || model.background_failures.contains_key(&url)
+        || model.is_being_fetched(&url)
+    {
+        return None;
+    }
+
+    if let Some(preview) = model.preview.take() {
+        preview.cancel();
+    }
+    let (preview, cancel) = model.start_loading(&url);
+    let request_id = preview.id;
+    model.preview = Some(preview);
+
+    Some(Command::GetResults {
+        request_id,
+        url,
+        options: model.fetch_options.clone(),
+        cancel,
+    })
+}
+
+/// Returns the commands to fetch pages in the background, within the
+/// configured concurrency limits: marked pages being crawled first, then (when
+/// prefetching) those of the selected link and its neighbors.
+pub(super) fn fetch_in_background(model: &mut Model) -> Vec<Command> {
+    let mut candidates: Vec<String> = model.crawl_queue.iter().cloned().collect();
+    if model.prefetch.enabled
+        && let Ok(results) = &model.results
+        && let Some(selected) = results.state.selected()
+    {
+        candidates.extend(
+            prefetch::candidate_indices(selected, model.prefetch.neighbors, results.items.len())
+                .into_iter()
+                .filter_map(|i| results.items.get(i))
+                .filter(|link| {
+                    !matches!(link.kind, LinkKind::Fragment | LinkKind::Download)
+                        && model.link_no_follow_rel(link).is_none()
+                })
+                .map(|link| link.url.clone()),
+        );
+    }
+
+    let mut cmds = Vec::new();
+    for url in candidates {
+        if model.prefetches.len() + model.crawls.len() >= model.prefetch.concurrency {
+            break;
+        }
+        if model.results_cache.contains_key(&url)
+            || model.background_failures.contains_key(&url)
+            || model.is_being_fetched(&url)
+        {
+            continue;
+        }
+        let host = prefetch::host(&url);
+        let fetches_from_host = model
+            .prefetches
+            .iter()
+            .chain(&model.crawls)
+            .filter(|p| prefetch::host(&p.url) == host)
+            .count();
+        if fetches_from_host >= model.prefetch.per_host {
+            continue;
+        }
+
+        let crawled = model.crawl_queue.contains(&url);
+        model.crawl_queue.retain(|queued| queued != &url);
+        let (fetch, cancel) = model.start_loading(&url);
+        cmds.push(Command::GetResults {
+            request_id: fetch.id,
+            url,
+            options: model.fetch_options.clone(),
+            cancel,
+        });
+        match crawled {
+            true => model.crawls.push(fetch),
+            false => model.prefetches.push(fetch),
         }
     }
+    // pages fetched some other way since being queued
+    model.crawl_queue.retain(|url| {
+        !model.results_cache.contains_key(url) && !model.background_failures.contains_key(url)
+    });
 
     cmds
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Link, Page};
+
+    fn select(model: &mut Model, index: usize) {
+        if let Ok(results) = &mut model.results {
+            results.state.select(Some(index));
+        }
+    }
+
+    fn selected(model: &Model) -> Option<usize> {
+        model.get_selected_url().map(|(_, index)| index)
+    }
+
+    // a model for a page linking to the cached pages "a" and "b"
+    fn navigation_model() -> Model {
+        let mut model = Model::with_links(&["https://a.com/", "https://b.com/"]);
+        for url in ["https://a.com/", "https://b.com/"] {
+            let links = vec![
+                Link::from_url("https://y.com/"),
+                Link::from_url("https://z.com/"),
+            ];
+            model
+                .results_cache
+                .insert(url.to_string(), Page::with_links(url, links));
+        }
+        model
+    }
+
+    // fails a fetch of an uncached page, as started from the current one
+    fn fail_fetch(model: &mut Model) {
+        let (loading, _cancel) = model.start_loading("https://c.com/");
+        let request_id = loading.id;
+        model.loading = Some(loading);
+        update(
+            model,
+            Message::ResultsFetched {
+                request_id,
+                page_result: Err("connection refused".to_string()),
+            },
+        );
+    }
+
+    #[test]
+    fn navigating_away_from_an_error_keeps_history_as_is() {
+        let mut model = navigation_model();
+        fail_fetch(&mut model);
+        assert!(model.results.is_err());
+        assert_eq!(model.history, ["https://example.com/"]);
+
+        model.navigate_to("https://b.com/");
+
+        assert_eq!(model.page_details.url, "https://b.com/");
+        assert_eq!(model.history, ["https://example.com/"]);
+    }
+
+    #[test]
+    fn going_forward_from_an_error_keeps_history_as_is() {
+        let mut model = navigation_model();
+        update(&mut model, Message::UrlChosen);
+        update(&mut model, Message::GoBack);
+        fail_fetch(&mut model);
+
+        update(&mut model, Message::GoForward);
+
+        assert_eq!(model.page_details.url, "https://a.com/");
+        assert_eq!(model.history, ["https://example.com/"]);
+    }
+
+    #[test]
+    fn going_back_and_forward_restores_selections() {
+        let mut model = navigation_model();
+        select(&mut model, 1);
+        update(&mut model, Message::UrlChosen);
+        select(&mut model, 1);
+
+        update(&mut model, Message::GoBack);
+        assert_eq!(model.page_details.url, "https://example.com/");
+        assert_eq!(selected(&model), Some(1));
+        assert_eq!(model.forward_history, ["https://b.com/"]);
+
+        update(&mut model, Message::GoForward);
+        assert_eq!(model.page_details.url, "https://b.com/");
+        assert_eq!(selected(&model), Some(1));
+        assert_eq!(model.history, ["https://example.com/"]);
+        assert!(model.forward_history.is_empty());
+    }
+
+    #[test]
+    fn going_somewhere_new_clears_forward_history() {
+        let mut model = navigation_model();
+        update(&mut model, Message::UrlChosen);
+        update(&mut model, Message::GoBack);
+        assert_eq!(model.forward_history, ["https://a.com/"]);
+
+        select(&mut model, 1);
+        update(&mut model, Message::UrlChosen);
+
+        assert_eq!(model.page_details.url, "https://b.com/");
+        assert!(model.forward_history.is_empty());
+    }
+
+    #[test]
+    fn going_past_either_end_of_history_is_an_error() {
+        let mut model = navigation_model();
+
+        update(&mut model, Message::GoBack);
+        assert!(model.user_message.is_some());
+        update(&mut model, Message::GoForward);
+        assert!(model.user_message.is_some());
+        assert_eq!(model.page_details.url, "https://example.com/");
+    }
+
+    fn prefetch_model(urls: &[&str]) -> Model {
+        let mut model = Model::with_links(urls);
+        model.prefetch.enabled = true;
+        model
+    }
+
+    fn fetched_urls(cmds: &[Command]) -> Vec<&str> {
+        cmds.iter()
+            .filter_map(|cmd| match cmd {
+                Command::GetResults { url, .. } => Some(url.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn links_near_the_cursor_are_prefetched_nearest_first() {
+        let mut model = prefetch_model(&[
+            "https://a.com/",
+            "https://b.com/",
+            "https://c.com/",
+            "https://d.com/",
+            "https://e.com/",
+        ]);
+        if let Ok(results) = &mut model.results {
+            results.state.select(Some(2));
+        }
+        model.prefetch.neighbors = 1;
+
+        let cmds = fetch_in_background(&mut model);
+
+        assert_eq!(
+            fetched_urls(&cmds),
+            ["https://c.com/", "https://d.com/", "https://b.com/"]
+        );
+        assert_eq!(model.prefetches.len(), 3);
+        assert!(fetch_in_background(&mut model).is_empty());
+    }
+
+    #[test]
+    fn prefetches_are_limited_overall_and_per_host() {
+        let mut model = prefetch_model(&[
+            "https://a.com/1",
+            "https://a.com/2",
+            "https://a.com/3",
+            "https://b.com/",
+            "https://c.com/",
+        ]);
+        model.prefetch.neighbors = 4;
+        model.prefetch.concurrency = 3;
+        model.prefetch.per_host = 2;
+
+        let cmds = fetch_in_background(&mut model);
+
+        assert_eq!(
+            fetched_urls(&cmds),
+            ["https://a.com/1", "https://a.com/2", "https://b.com/"]
+        );
+    }
+
+    #[test]
+    fn cached_failed_and_skipped_links_are_not_prefetched() {
+        let mut model = prefetch_model(&[
+            "https://example.com/",
+            "https://a.com/",
+            "https://b.com/",
+            "https://c.com/",
+        ]);
+        model
+            .background_failures
+            .insert("https://a.com/".to_string(), "timed out".to_string());
+        if let Ok(results) = &mut model.results {
+            results.items[2].kind = LinkKind::Download;
+        }
+        model.prefetch.neighbors = 3;
+
+        let cmds = fetch_in_background(&mut model);
+
+        assert_eq!(fetched_urls(&cmds), ["https://c.com/"]);
+    }
+
+    #[test]
+    fn disabling_prefetching_cancels_prefetches() {
+        let mut model = prefetch_model(&["https://a.com/", "https://b.com/"]);
+        fetch_in_background(&mut model);
+        assert!(!model.prefetches.is_empty());
+
+        update(&mut model, Message::TogglePrefetch);
+
+        assert!(!model.prefetch.enabled);
+        assert!(model.prefetches.is_empty());
+    }
+
+    fn marks_model() -> Model {
+        Model::with_links(&["https://a.com/", "https://b.com/", "https://c.com/"])
+    }
+
+    #[test]
+    fn toggling_a_mark_moves_to_the_next_result() {
+        let mut model = marks_model();
+
+        update(&mut model, Message::ToggleMark);
+        update(&mut model, Message::ToggleMark);
+        assert_eq!(model.marks, ["https://a.com/", "https://b.com/"]);
+        assert_eq!(selected(&model), Some(2));
+
+        select(&mut model, 0);
+        update(&mut model, Message::ToggleMark);
+        assert_eq!(model.marks, ["https://b.com/"]);
+    }
+
+    #[test]
+    fn visual_marking_marks_the_range_selected() {
+        let mut model = marks_model();
+        model.mark("https://b.com/");
+
+        select(&mut model, 2);
+        update(&mut model, Message::ToggleVisualMarking);
+        select(&mut model, 1);
+        update(&mut model, Message::ToggleVisualMarking);
+
+        assert_eq!(model.marks, ["https://b.com/", "https://c.com/"]);
+        assert_eq!(model.visual_anchor, None);
+    }
+
+    #[test]
+    fn marks_can_be_inverted() {
+        let mut model = marks_model();
+        model.mark("https://b.com/");
+
+        update(&mut model, Message::InvertMarks);
+
+        assert_eq!(model.marks, ["https://a.com/", "https://c.com/"]);
+    }
+
+    #[test]
+    fn marked_pages_are_crawled_without_prefetching() {
+        let mut model = marks_model();
+        model.mark("https://a.com/");
+        model.mark("https://c.com/");
+
+        let cmds = update(&mut model, Message::CrawlMarks);
+
+        assert_eq!(fetched_urls(&cmds), ["https://a.com/", "https://c.com/"]);
+        assert!(model.crawl_queue.is_empty());
+    }
+
+    #[test]
+    fn switching_to_the_active_tab_keeps_its_fetch() {
+        let mut model = navigation_model();
+        model.open_tab();
+        let (loading, _cancel) = model.start_loading("https://c.com/");
+        model.loading = Some(loading);
+
+        update(&mut model, Message::GoToTab(1));
+        assert!(model.loading.is_some());
+
+        update(&mut model, Message::GoToTab(0));
+        assert!(model.loading.is_none());
+    }
+
+    #[test]
+    fn disabling_prefetching_keeps_crawling() {
+        let mut model = marks_model();
+        model.mark("https://a.com/");
+        model.mark("https://c.com/");
+        update(&mut model, Message::CrawlMarks);
+
+        update(&mut model, Message::TogglePrefetch);
+        assert!(!model.prefetches.is_empty());
+        update(&mut model, Message::TogglePrefetch);
+
+        assert!(model.prefetches.is_empty());
+        assert_eq!(model.crawls.len(), 2);
+        assert!(model.is_being_fetched("https://c.com/"));
+    }
+}
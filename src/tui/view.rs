@@ -1,15 +1,29 @@
 use super::common::*;
-use super::model::{MessageKind, Model, Results};
-use crate::domain::PageDetails;
+use super::highlight::highlight_html;
+use super::model::{MessageKind, Model, Prompt, PromptKind, Results, parse_url_input};
+use super::search::{Search, displayed_url};
+use super::visits::title;
+use super::wrap::wrap_lines;
+use crate::domain::{
+    Link, LinkKind, LinkSource, Page, PageDetails, Reader, ReaderBlockKind, ReaderSpan,
+    ResponseInfo,
+};
 use ratatui::{
     Frame,
     layout::{Alignment, Constraint, Layout, Rect},
-    style::{Style, Stylize},
+    style::{Color, Style, Stylize},
     text::{Line, Span},
-    widgets::{Block, List, ListDirection, ListItem, Padding, Paragraph, Wrap},
+    widgets::{Block, List, ListDirection, ListItem, ListState, Padding, Paragraph, Wrap},
 };
+use regex::Regex;
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
 
 const HELP_CONTENTS: &str = include_str!("static/help.txt");
+const BREADCRUMB_SEPARATOR: &str = " › ";
+const BREADCRUMB_LABEL_MAX_CHARS: usize = 32;
+const TAB_LABEL_MAX_CHARS: usize = 24;
+const SPINNER_FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
 
 pub fn view(model: &mut Model, frame: &mut Frame) {
     if model.terminal_too_small {
@@ -19,7 +33,13 @@ pub fn view(model: &mut Model, frame: &mut Frame) {
 
     match model.active_pane {
         Pane::Help => render_help_view(model, frame),
-        _ => render_list_view(model, frame),
+        Pane::PageDetails => render_page_details_view(model, frame),
+        Pane::Redirects => render_redirects_view(model, frame),
+        Pane::Response => render_response_view(model, frame),
+        Pane::Reader => render_reader_view(model, frame),
+        Pane::Source => render_source_view(model, frame),
+        Pane::History => render_history_view(model, frame),
+        Pane::ResultsList => render_list_view(model, frame),
     }
 }
 
@@ -47,27 +67,403 @@ Press (q/<ctrl+c>/<esc> to exit)
 }
 
 fn render_help_view(model: &mut Model, frame: &mut Frame) {
+    let lines: Vec<Line<'static>> = HELP_CONTENTS.lines().map(Line::from).collect();
+    render_scrollable_view(model, frame, " help ", HELP_COLOR, lines);
+}
+
+fn render_page_details_view(model: &mut Model, frame: &mut Frame) {
+    let lines = page_details_lines(&model.page_details);
+    render_scrollable_view(model, frame, " page details ", SECONDARY_COLOR, lines);
+}
+
+fn page_details_lines(details: &PageDetails) -> Vec<Line<'static>> {
+    let field = |label: &str, value: &Option<String>| {
+        Line::from(vec![
+            Span::styled(format!("{:<16}", label), Style::new().fg(PRIMARY_COLOR)),
+            Span::from(value.clone().unwrap_or("-".to_string())),
+        ])
+    };
+    let heading = |title: &str| Line::from(Span::styled(title.to_string(), Style::new().bold()));
+
+    let mut lines = vec![
+        field("URL", &Some(details.url.clone())),
+        field("Title", &details.title),
+        field("Description", &details.description),
+        field("Canonical URL", &details.canonical_url),
+        field("Language", &details.language),
+        field("Robots", &details.robots),
+        field("Type", &details.og_type),
+        field("Site name", &details.site_name),
+        field("Image", &details.image),
+        field("Favicon", &details.favicon),
+        field(
+            "Redirects to",
+            &details
+                .client_redirect
+                .as_ref()
+                .map(|r| format!("{} (via {})", r.url, r.kind)),
+        ),
+    ];
+
+    if !details.twitter.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(heading("Twitter card"));
+        lines.push(field("Card", &details.twitter.card));
+        lines.push(field("Site", &details.twitter.site));
+        lines.push(field("Creator", &details.twitter.creator));
+        lines.push(field("Title", &details.twitter.title));
+        lines.push(field("Description", &details.twitter.description));
+        lines.push(field("Image", &details.twitter.image));
+    }
+
+    for (i, block) in details.json_ld.iter().enumerate() {
+        lines.push(Line::from(""));
+        lines.push(heading(&format!(
+            "JSON-LD ({}/{})",
+            i + 1,
+            details.json_ld.len()
+        )));
+        let pretty = match block {
+            serde_json::Value::String(raw) => raw.clone(),
+            value => serde_json::to_string_pretty(value).unwrap_or_default(),
+        };
+        lines.extend(pretty.lines().map(|l| Line::from(l.to_string())));
+    }
+
+    lines
+}
+
+fn render_history_view(model: &mut Model, frame: &mut Frame) {
     let layout = Layout::default()
         .direction(ratatui::layout::Direction::Vertical)
         .constraints(vec![Constraint::Min(21), Constraint::Length(1)])
         .split(frame.area());
 
-    let lines: Vec<Line<'_>> = HELP_CONTENTS.lines().map(Line::from).collect();
+    let current = model.visits.current();
+    let items: Vec<ListItem> = model
+        .visits
+        .rows()
+        .into_iter()
+        .map(|row| {
+            let label_style = if row.index == current {
+                Style::new().fg(URL_COLOR).bold()
+            } else {
+                Style::new()
+            };
+            let mut spans = vec![
+                Span::styled(row.prefix, Style::new().fg(BREADCRUMB_COLOR)),
+                Span::styled(
+                    title(&model.results_cache, &row.url)
+                        .unwrap_or(&row.url)
+                        .to_string(),
+                    label_style,
+                ),
+            ];
+            if title(&model.results_cache, &row.url).is_some() {
+                spans.push(Span::styled(
+                    format!("  {}", row.url),
+                    Style::new().fg(BREADCRUMB_COLOR),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(pane_block(
+            " history (enter: jump, m: export markdown, J: export json) ",
+            SECONDARY_COLOR,
+        ))
+        .style(Style::new().white())
+        .highlight_symbol("> ")
+        .highlight_style(Style::new().fg(PRIMARY_COLOR))
+        .direction(ListDirection::TopToBottom);
+
+    frame.render_stateful_widget(list, layout[0], &mut model.visits_state);
+    render_status_bar(model, frame, layout[1]);
+}
+
+fn render_redirects_view(model: &mut Model, frame: &mut Frame) {
+    let lines = redirect_lines(&model.page_details);
+    render_scrollable_view(model, frame, " redirects ", REDIRECT_COLOR, lines);
+}
+
+fn redirect_lines(details: &PageDetails) -> Vec<Line<'static>> {
+    if details.redirects.is_empty() {
+        return vec![
+            Line::from(details.url.clone()),
+            Line::from(""),
+            Line::from(Span::styled(
+                "no redirects were followed",
+                Style::new().fg(PRIMARY_COLOR),
+            )),
+        ];
+    }
+
+    let mut lines = Vec::new();
+    for (i, hop) in details.redirects.iter().enumerate() {
+        let via = match &hop.client {
+            Some(kind) => kind.to_string(),
+            None => hop.status.to_string(),
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:>2}. ", i + 1), Style::new().fg(PRIMARY_COLOR)),
+            Span::from(hop.url.clone()),
+        ]));
+        lines.push(Line::from(vec![
+            Span::from("    "),
+            Span::styled(format!("{} → ", via), Style::new().fg(REDIRECT_COLOR)),
+            Span::from(hop.location.clone()),
+        ]));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled("final URL ", Style::new().fg(PRIMARY_COLOR)),
+        Span::from(details.final_url().to_string()),
+    ]));
+
+    lines
+}
+
+const SECURITY_HEADERS: [&str; 6] = [
+    "strict-transport-security",
+    "content-security-policy",
+    "x-frame-options",
+    "x-content-type-options",
+    "referrer-policy",
+    "permissions-policy",
+];
+
+fn render_response_view(model: &mut Model, frame: &mut Frame) {
+    let lines = match model.results_cache.get(&model.page_details.url) {
+        Some(page) => response_lines(&page.response),
+        None => vec![Line::from("no response recorded for this page")],
+    };
+    render_scrollable_view(model, frame, " response ", INFO_MESSAGE_COLOR, lines);
+}
+
+fn response_lines(response: &ResponseInfo) -> Vec<Line<'static>> {
+    let field = |label: &str, value: String| {
+        Line::from(vec![
+            Span::styled(format!("{:<28}", label), Style::new().fg(PRIMARY_COLOR)),
+            Span::from(value),
+        ])
+    };
+    let header = |name: &str| field(name, response.header(name).unwrap_or("-").to_string());
+    let heading = |title: &str| Line::from(Span::styled(title.to_string(), Style::new().bold()));
+
+    let mut lines = vec![
+        field("Status", response.status.to_string()),
+        field("TTFB", format!("{} ms", response.ttfb.as_millis())),
+        field("Total time", format!("{} ms", response.total.as_millis())),
+        field("Body size", human_size(response.body_size)),
+        Line::from(""),
+        header("content-type"),
+        header("cache-control"),
+        header("server"),
+        Line::from(""),
+        heading("Security headers"),
+    ];
+
+    for name in SECURITY_HEADERS {
+        lines.push(match response.header(name) {
+            Some(value) => field(name, value.to_string()),
+            None => Line::from(vec![
+                Span::styled(format!("{:<28}", name), Style::new().fg(PRIMARY_COLOR)),
+                Span::styled("missing", Style::new().fg(ERROR_COLOR)),
+            ]),
+        });
+    }
+
+    lines.push(Line::from(""));
+    lines.push(heading(&format!(
+        "All headers ({})",
+        response.headers.len()
+    )));
+    for (name, value) in &response.headers {
+        lines.push(field(name, value.clone()));
+    }
+
+    lines
+}
+
+fn human_size(bytes: usize) -> String {
+    match bytes {
+        b if b < 1024 => format!("{} B", b),
+        b if b < 1024 * 1024 => format!("{:.1} KB", b as f64 / 1024.0),
+        b => format!("{:.1} MB", b as f64 / (1024.0 * 1024.0)),
+    }
+}
+
+fn render_reader_view(model: &mut Model, frame: &mut Frame) {
+    let lines = match model.results_cache.get(&model.page_details.url) {
+        Some(page) if !page.reader.blocks.is_empty() => reader_lines(&page.reader),
+        _ => vec![Line::from("no readable content found on this page")],
+    };
+    let title = format!(
+        " {} ",
+        model.page_details.title.as_deref().unwrap_or("reader")
+    );
+    render_scrollable_view(model, frame, &title, SECONDARY_COLOR, lines);
+}
+
+fn reader_lines(reader: &Reader) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    let mut previous_kind = None;
+    for block in &reader.blocks {
+        // list items are kept together
+        let continues_list = block.kind == ReaderBlockKind::ListItem
+            && previous_kind == Some(ReaderBlockKind::ListItem);
+        if !lines.is_empty() && !continues_list {
+            lines.push(Line::from(""));
+        }
+        previous_kind = Some(block.kind);
+
+        let (prefix, style) = match block.kind {
+            ReaderBlockKind::Heading(level) => (
+                format!("{} ", "#".repeat(level as usize)),
+                Style::new().bold().fg(PRIMARY_COLOR),
+            ),
+            ReaderBlockKind::Paragraph => (String::new(), Style::new()),
+            ReaderBlockKind::ListItem => ("• ".to_string(), Style::new()),
+            ReaderBlockKind::Quote => ("│ ".to_string(), Style::new().italic()),
+            ReaderBlockKind::Preformatted => {
+                for span in &block.spans {
+                    if let ReaderSpan::Text(text) = span {
+                        lines.extend(text.lines().map(|l| {
+                            Line::from(Span::styled(l.to_string(), Style::new().fg(URL_COLOR)))
+                        }));
+                    }
+                }
+                continue;
+            }
+        };
+
+        let mut spans = vec![Span::styled(prefix, style)];
+        for span in &block.spans {
+            match span {
+                ReaderSpan::Text(text) => spans.push(Span::styled(text.clone(), style)),
+                ReaderSpan::Link { text, number } => {
+                    spans.push(Span::styled(text.clone(), style.underlined()));
+                    spans.push(Span::styled(
+                        format!("[{}]", number),
+                        Style::new().fg(REL_BADGE_COLOR),
+                    ));
+                }
+            }
+        }
+        lines.push(Line::from(spans));
+    }
+
+    lines
+}
+
+fn render_source_view(model: &mut Model, frame: &mut Frame) {
+    let layout = Layout::default()
+        .direction(ratatui::layout::Direction::Vertical)
+        .constraints(vec![Constraint::Min(21), Constraint::Length(1)])
+        .split(frame.area());
+
+    let title = match &model.source_search {
+        Some(query) => format!(" source [/{}] ", query),
+        None => " source ".to_string(),
+    };
+    let block = pane_block(&title, URL_COLOR);
+    let inner = block.inner(layout[0]);
+
+    let lines = match model.results_cache.get(&model.page_details.url) {
+        Some(page) => {
+            let total = page.source.lines().count();
+            model.pane_scroll = model
+                .pane_scroll
+                .min(total.saturating_sub(1).min(u16::MAX as usize) as u16);
+            let start = model.pane_scroll as usize;
+            let gutter_width = total.to_string().len();
+            let query = model.source_search.as_ref().map(|q| q.to_lowercase());
+
+            highlight_html(&page.source, start..start + inner.height as usize)
+                .into_iter()
+                .zip(page.source.lines().skip(start))
+                .enumerate()
+                .map(|(i, (line, raw))| {
+                    let number = start + i;
+                    let is_match = query
+                        .as_ref()
+                        .is_some_and(|q| raw.to_lowercase().contains(q));
+                    let gutter_style = match is_match {
+                        true => Style::new()
+                            .bg(SEARCH_MATCH_COLOR)
+                            .fg(SECTION_TITLE_FG_COLOR),
+                        false => Style::new().fg(REL_BADGE_DIM_COLOR),
+                    };
+                    let mut spans = vec![
+                        Span::styled(
+                            format!("{:>w$}", number + 1, w = gutter_width),
+                            gutter_style,
+                        ),
+                        Span::from(" "),
+                    ];
+                    spans.extend(line.spans);
+
+                    let line = Line::from(spans);
+                    match model.source_highlight.contains(&number) {
+                        true => line.style(Style::new().bg(SOURCE_HIGHLIGHT_BG_COLOR).bold()),
+                        false => line,
+                    }
+                })
+                .collect()
+        }
+        None => vec![Line::from("no source recorded for this page")],
+    };
 
     let p = Paragraph::new(lines)
-        .block(
-            Block::bordered()
-                .border_style(Style::default().fg(PRIMARY_BORDER_COLOR))
-                .title_style(
-                    Style::new()
-                        .bold()
-                        .bg(HELP_COLOR)
-                        .fg(SECTION_TITLE_FG_COLOR),
-                )
-                .title(" help ")
-                .padding(Padding::new(2, 0, 1, 0)),
+        .block(block)
+        .style(Style::new().white());
+
+    frame.render_widget(p, layout[0]);
+    render_status_bar(model, frame, layout[1]);
+}
+
+fn pane_block(title: &str, title_color: Color) -> Block<'_> {
+    Block::bordered()
+        .border_style(Style::default().fg(PRIMARY_BORDER_COLOR))
+        .title_style(
+            Style::new()
+                .bold()
+                .bg(title_color)
+                .fg(SECTION_TITLE_FG_COLOR),
         )
+        .title(title)
+        .padding(Padding::new(2, 0, 1, 0))
+}
+
+fn render_scrollable_view(
+    model: &mut Model,
+    frame: &mut Frame,
+    title: &str,
+    title_color: Color,
+    lines: Vec<Line<'static>>,
+) {
+    let layout = Layout::default()
+        .direction(ratatui::layout::Direction::Vertical)
+        .constraints(vec![Constraint::Min(21), Constraint::Length(1)])
+        .split(frame.area());
+
+    let block = pane_block(title, title_color);
+    let inner = block.inner(layout[0]);
+    // scrolling is by rows, and long lines (eg. paragraphs in reader mode)
+    // take up several of them
+    let lines = wrap_lines(lines, inner.width);
+    let max_scroll = lines.len().saturating_sub(usize::from(inner.height));
+    model.pane_scroll = model
+        .pane_scroll
+        .min(max_scroll.min(u16::MAX as usize) as u16);
+
+    let p = Paragraph::new(lines)
+        .block(block)
         .style(Style::new().white())
+        .scroll((model.pane_scroll, 0))
         .alignment(Alignment::Left);
 
     frame.render_widget(p, layout[0]);
@@ -75,6 +471,11 @@ fn render_help_view(model: &mut Model, frame: &mut Frame) {
 }
 
 fn render_status_bar(model: &Model, frame: &mut Frame, rect: Rect) {
+    if let Some(prompt) = &model.prompt {
+        render_prompt(prompt, &model.search, frame, rect);
+        return;
+    }
+
     let mut status_bar_lines = vec![Span::styled(
         TITLE,
         Style::new()
@@ -108,6 +509,20 @@ fn render_status_bar(model: &Model, frame: &mut Frame, rect: Rect) {
         )));
     }
 
+    if let Some(loading) = &model.loading {
+        let elapsed = loading.started_at.elapsed();
+        let spinner = SPINNER_FRAMES[(elapsed.as_millis() / 100) as usize % SPINNER_FRAMES.len()];
+        status_bar_lines.push(Span::styled(
+            format!(
+                " {} loading {} ({:.1}s, esc to cancel)",
+                spinner,
+                loading.url,
+                elapsed.as_secs_f32()
+            ),
+            Style::new().fg(INFO_MESSAGE_COLOR),
+        ));
+    }
+
     if let Some(msg) = &model.user_message {
         let span = match msg.kind {
             MessageKind::Info => Span::styled(
@@ -129,17 +544,74 @@ fn render_status_bar(model: &Model, frame: &mut Frame, rect: Rect) {
     frame.render_widget(&status_bar, rect);
 }
 
+fn render_prompt(prompt: &Prompt, search: &Search, frame: &mut Frame, rect: Rect) {
+    let mut spans = vec![Span::styled(
+        format!(" {} ", prompt.kind.label()),
+        Style::new()
+            .bold()
+            .bg(PRIMARY_COLOR)
+            .fg(SECTION_TITLE_FG_COLOR),
+    )];
+    if prompt.kind == PromptKind::Search {
+        let flag = |label: &'static str, on: bool| match on {
+            true => Span::styled(label, Style::new().fg(SEARCH_MATCH_COLOR).bold()),
+            false => Span::styled(label, Style::new().fg(REL_BADGE_DIM_COLOR)),
+        };
+        spans.push(Span::from(" "));
+        spans.push(flag("Aa", search.case_sensitive));
+        spans.push(Span::from(" "));
+        spans.push(flag(".*", search.regex));
+    }
+    spans.push(Span::from(": "));
+
+    let label_width: usize = spans.iter().map(|s| s.content.chars().count()).sum();
+    let cursor_x = rect.x + (label_width + prompt.input.chars().count()) as u16;
+
+    spans.push(Span::from(prompt.input.as_str()));
+    if let Some(completion) = &prompt.completion {
+        spans.push(Span::styled(
+            format!(
+                "  ({}/{})",
+                completion.index + 1,
+                completion.candidates.len()
+            ),
+            Style::new().fg(INFO_MESSAGE_COLOR),
+        ));
+    }
+    let error = match prompt.kind {
+        PromptKind::Search => search.error().map(|e| e.to_string()),
+        PromptKind::OpenUrl if !prompt.input.is_empty() => parse_url_input(&prompt.input).err(),
+        _ => None,
+    };
+    if let Some(error) = error {
+        spans.push(Span::styled(
+            format!("  ({})", error),
+            Style::new().fg(ERROR_COLOR),
+        ));
+    }
+    let prompt_line = Line::from(spans);
+
+    frame.render_widget(Paragraph::new(prompt_line), rect);
+    frame.set_cursor_position((cursor_x.min(rect.right().saturating_sub(1)), rect.y));
+}
+
 fn render_results_list_and_details(
     details: &PageDetails,
     results: &mut Results,
+    title: String,
+    listing: &LinkListing,
     frame: &mut Frame,
     rect: Rect,
 ) {
-    let title = " results ";
     let items: Vec<ListItem> = results
         .items
         .iter()
-        .map(|s| ListItem::new(s.clone()))
+        .enumerate()
+        .map(|(i, link)| {
+            let marked = listing.marks.iter().any(|m| m == &link.url)
+                || listing.visual.as_ref().is_some_and(|r| r.contains(&i));
+            link_list_item(link, listing, marked)
+        })
         .collect();
 
     let layout = Layout::default()
@@ -147,14 +619,31 @@ fn render_results_list_and_details(
         .constraints(vec![Constraint::Percentage(80), Constraint::Percentage(20)])
         .split(rect);
 
-    let details_str = match (&details.title, &details.description) {
-        (None, None) => "No details found".to_string(),
-        (None, Some(d)) => d.clone(),
-        (Some(t), None) => format!("Title: {}", t),
-        (Some(t), Some(d)) => format!("Title: {}\n\n{}", d, t),
+    let selected = results
+        .state
+        .selected()
+        .and_then(|i| results.items.get(i))
+        .filter(|link| link.heading.is_some() || link.context.is_some());
+    let (details_title, details_lines) = match selected {
+        Some(link) => (" link context ", link_context_lines(link)),
+        None => {
+            let details_str = match (&details.title, &details.description) {
+                (None, None) => "No details found".to_string(),
+                (None, Some(d)) => d.clone(),
+                (Some(t), None) => format!("Title: {}", t),
+                (Some(t), Some(d)) => format!("Title: {}\n\n{}", t, d),
+            };
+            (
+                " page details ",
+                details_str
+                    .lines()
+                    .map(|l| Line::from(l.to_string()))
+                    .collect(),
+            )
+        }
     };
 
-    let details_paragraph = Paragraph::new(details_str)
+    let details_paragraph = Paragraph::new(details_lines)
         .block(
             Block::bordered()
                 .border_style(Style::default().fg(PRIMARY_BORDER_COLOR))
@@ -164,7 +653,7 @@ fn render_results_list_and_details(
                         .bg(SECONDARY_COLOR)
                         .fg(SECTION_TITLE_FG_COLOR),
                 )
-                .title(" page details ")
+                .title(details_title)
                 .padding(Padding::new(1, 1, 1, 1)),
         )
         .wrap(Wrap { trim: false })
@@ -172,7 +661,12 @@ fn render_results_list_and_details(
         .alignment(Alignment::Left);
 
     if items.is_empty() {
-        let list = Paragraph::new("no urls")
+        let empty_message = if results.total > 0 {
+            "no urls match the current filter"
+        } else {
+            "no urls"
+        };
+        let list = Paragraph::new(empty_message)
             .block(
                 Block::bordered()
                     .border_style(Style::default().fg(PRIMARY_BORDER_COLOR))
@@ -182,7 +676,7 @@ fn render_results_list_and_details(
                             .bg(SECONDARY_COLOR)
                             .fg(SECTION_TITLE_FG_COLOR),
                     )
-                    .title(title)
+                    .title(title.as_str())
                     .padding(Padding::new(1, 0, 1, 1)),
             )
             .style(Style::new().white().on_black())
@@ -216,6 +710,130 @@ fn render_results_list_and_details(
     frame.render_widget(&details_paragraph, layout[1]);
 }
 
+fn link_context_lines(link: &Link) -> Vec<Line<'static>> {
+    let field = |label: &str, value: &str| {
+        Line::from(vec![
+            Span::styled(format!("{:<7}", label), Style::new().fg(PRIMARY_COLOR)),
+            Span::from(value.to_string()),
+        ])
+    };
+
+    let mut lines = Vec::new();
+    if let Some(text) = &link.text {
+        lines.push(field("Text", text));
+    }
+    if let Some(heading) = &link.heading {
+        lines.push(field("Under", heading));
+    }
+    if let Some(context) = &link.context {
+        lines.push(Line::from(Span::styled(
+            format!("\"{}\"", context),
+            Style::new().italic(),
+        )));
+    }
+
+    lines
+}
+
+/// How links are shown in a list.
+struct LinkListing<'a> {
+    show_raw_urls: bool,
+    search: Option<&'a Regex>,
+    /// when set, links whose pages are cached are marked as such
+    cache: Option<&'a HashMap<String, Page>>,
+    marks: &'a [String],
+    /// results in the range being selected for marking
+    visual: Option<RangeInclusive<usize>>,
+}
+
+fn link_list_item<'a>(link: &'a Link, listing: &LinkListing, marked: bool) -> ListItem<'a> {
+    let url = displayed_url(link, listing.show_raw_urls);
+
+    let mut spans = Vec::new();
+    if !listing.marks.is_empty() || listing.visual.is_some() {
+        spans.push(match marked {
+            true => Span::styled("✓ ", Style::new().fg(SECONDARY_COLOR).bold()),
+            false => Span::from("  "),
+        });
+    }
+    if let Some(cache) = listing.cache {
+        spans.push(match cache.contains_key(&link.url) {
+            true => Span::styled("● ", Style::new().fg(INFO_MESSAGE_COLOR)),
+            false => Span::from("  "),
+        });
+    }
+    spans.push(Span::styled(
+        format!("{} ", link.kind.marker()),
+        Style::new().fg(link_kind_color(link.kind)),
+    ));
+    if link.source == LinkSource::ClientRedirect {
+        spans.push(Span::styled(
+            "redirects to",
+            Style::new()
+                .bold()
+                .bg(REDIRECT_COLOR)
+                .fg(SECTION_TITLE_FG_COLOR),
+        ));
+        spans.push(Span::from(" "));
+    }
+    spans.extend(highlight_matches(url, listing.search));
+    if link.count > 1 {
+        spans.push(Span::styled(
+            format!(" ×{}", link.count),
+            Style::new().fg(INFO_MESSAGE_COLOR),
+        ));
+    }
+    for rel in &link.rel {
+        spans.push(Span::from(" "));
+        spans.push(rel_badge(rel));
+    }
+
+    ListItem::new(Line::from(spans))
+}
+
+fn highlight_matches<'a>(text: &'a str, search: Option<&Regex>) -> Vec<Span<'a>> {
+    let Some(search) = search else {
+        return vec![Span::from(text)];
+    };
+
+    let mut spans = Vec::new();
+    let mut last = 0;
+    for m in search.find_iter(text).filter(|m| !m.is_empty()) {
+        spans.push(Span::from(&text[last..m.start()]));
+        spans.push(Span::styled(
+            m.as_str(),
+            Style::new()
+                .bg(SEARCH_MATCH_COLOR)
+                .fg(SECTION_TITLE_FG_COLOR),
+        ));
+        last = m.end();
+    }
+    spans.push(Span::from(&text[last..]));
+
+    spans
+}
+
+fn rel_badge(rel: &str) -> Span<'_> {
+    let style = match rel {
+        "nofollow" | "sponsored" | "ugc" => {
+            Style::new().bg(REL_BADGE_COLOR).fg(SECTION_TITLE_FG_COLOR)
+        }
+        _ => Style::new().fg(REL_BADGE_DIM_COLOR),
+    };
+
+    Span::styled(format!("[{}]", rel), style)
+}
+
+fn link_kind_color(kind: LinkKind) -> Color {
+    match kind {
+        LinkKind::Fragment => FRAGMENT_LINK_COLOR,
+        LinkKind::Download => DOWNLOAD_LINK_COLOR,
+        LinkKind::Internal => INTERNAL_LINK_COLOR,
+        LinkKind::Subdomain => SUBDOMAIN_LINK_COLOR,
+        LinkKind::External => EXTERNAL_LINK_COLOR,
+    }
+}
+
 fn render_results_error(error: &str, frame: &mut Frame, rect: Rect) {
     let title = " error ";
     let details = Paragraph::new(error)
@@ -237,9 +855,68 @@ fn render_results_error(error: &str, frame: &mut Frame, rect: Rect) {
     frame.render_widget(&details, rect);
 }
 
+fn results_title(model: &Model, results: &Results) -> String {
+    let mut title = if results.items.len() < results.total {
+        format!(
+            " results ({}/{}) [{}] ",
+            results.items.len(),
+            results.total,
+            model.order
+        )
+    } else {
+        format!(" results [{}] ", model.order)
+    };
+    if !model.fuzzy_filter.is_empty() {
+        title.push_str(&format!("~{} ", model.fuzzy_filter));
+    }
+    if model.search.is_active() {
+        let matches = results
+            .items
+            .iter()
+            .filter(|link| model.search.matches(link, model.show_raw_urls))
+            .count();
+        title.push_str(&format!("/{} ({} matches) ", model.search.query, matches));
+    }
+    if !model.marks.is_empty() {
+        title.push_str(&format!("[{} marked] ", model.marks.len()));
+    }
+    if model.visual_anchor.is_some() {
+        title.push_str("[marking range, v to mark] ");
+    }
+    if model.prefetch.enabled {
+        title.push_str(&format!("[prefetching {}] ", model.prefetches.len()));
+    }
+    if !model.crawls.is_empty() || !model.crawl_queue.is_empty() {
+        title.push_str(&format!(
+            "[crawling {}] ",
+            model.crawls.len() + model.crawl_queue.len()
+        ));
+    }
+
+    title
+}
+
 fn render_results(model: &mut Model, frame: &mut Frame, rect: Rect) {
+    let title = match &model.results {
+        Ok(r) => results_title(model, r),
+        Err(_) => String::new(),
+    };
+    let visual_range = model.visual_range();
     match &mut model.results {
-        Ok(r) => render_results_list_and_details(&model.page_details, r, frame, rect),
+        Ok(r) => render_results_list_and_details(
+            &model.page_details,
+            r,
+            title,
+            &LinkListing {
+                show_raw_urls: model.show_raw_urls,
+                search: model.search.matcher(),
+                cache: model.prefetch.enabled.then_some(&model.results_cache),
+                marks: &model.marks,
+                visual: visual_range,
+            },
+            frame,
+            rect,
+        ),
         Err(e) => render_results_error(e, frame, rect),
     }
 }
@@ -247,9 +924,234 @@ fn render_results(model: &mut Model, frame: &mut Frame, rect: Rect) {
 fn render_list_view(model: &mut Model, frame: &mut Frame) {
     let layout = Layout::default()
         .direction(ratatui::layout::Direction::Vertical)
-        .constraints(vec![Constraint::Min(10), Constraint::Length(1)])
+        .constraints(vec![
+            Constraint::Length(if model.tab_count() > 1 { 1 } else { 0 }),
+            Constraint::Length(1),
+            Constraint::Min(10),
+            Constraint::Length(1),
+        ])
         .split(frame.area());
 
-    render_results(model, frame, layout[0]);
-    render_status_bar(model, frame, layout[1]);
+    render_tab_bar(model, frame, layout[0]);
+    render_breadcrumb(model, frame, layout[1]);
+    if model.miller_columns {
+        render_miller_columns(model, frame, layout[2]);
+    } else {
+        render_results(model, frame, layout[2]);
+    }
+    render_status_bar(model, frame, layout[3]);
+}
+
+fn render_tab_bar(model: &Model, frame: &mut Frame, rect: Rect) {
+    let mut spans = Vec::new();
+    for (i, details) in model.tab_pages().into_iter().enumerate() {
+        let label = format!(" {} {} ", i + 1, tab_label(details));
+        spans.push(if i == model.active_tab {
+            Span::styled(
+                label,
+                Style::new()
+                    .bold()
+                    .bg(SECONDARY_COLOR)
+                    .fg(SECTION_TITLE_FG_COLOR),
+            )
+        } else {
+            Span::styled(label, Style::new().fg(BREADCRUMB_COLOR))
+        });
+        spans.push(Span::from(" "));
+    }
+
+    frame.render_widget(Paragraph::new(Line::from(spans)), rect);
+}
+
+fn tab_label(details: &PageDetails) -> String {
+    let label = details
+        .title
+        .clone()
+        .unwrap_or_else(|| details.url.trim_start_matches("https://").to_string());
+
+    truncate_label(label, TAB_LABEL_MAX_CHARS)
+}
+
+fn truncate_label(label: String, max_chars: usize) -> String {
+    if label.chars().count() > max_chars {
+        let truncated: String = label.chars().take(max_chars - 1).collect();
+        format!("{}…", truncated)
+    } else {
+        label
+    }
+}
+
+// the previous page (with the link followed from it selected), the current
+// results, and the pages linked to by the selected result
+fn render_miller_columns(model: &mut Model, frame: &mut Frame, rect: Rect) {
+    let layout = Layout::default()
+        .direction(ratatui::layout::Direction::Horizontal)
+        .constraints(vec![
+            Constraint::Percentage(25),
+            Constraint::Percentage(45),
+            Constraint::Percentage(30),
+        ])
+        .split(rect);
+
+    match model
+        .history
+        .back()
+        .and_then(|url| model.results_cache.get(url))
+    {
+        Some(parent) => {
+            let links = parent.visible_links(&model.filter, model.order);
+            let selected = links.iter().position(|l| l.url == model.page_details.url);
+            render_column(
+                &column_title(parent.details.title.as_deref(), &parent.details.url),
+                &links,
+                selected,
+                model.show_raw_urls,
+                frame,
+                layout[0],
+            );
+        }
+        None => render_column_message(
+            " previous ",
+            "at the start of navigation history",
+            frame,
+            layout[0],
+        ),
+    }
+
+    render_results(model, frame, layout[1]);
+
+    let Some(link) = model.get_selected_link() else {
+        render_column_message(" preview ", "nothing selected", frame, layout[2]);
+        return;
+    };
+    if let Some(page) = model.results_cache.get(&link.url) {
+        let links = page.visible_links(&model.filter, model.order);
+        render_column(
+            &column_title(page.details.title.as_deref(), &page.details.url),
+            &links,
+            None,
+            model.show_raw_urls,
+            frame,
+            layout[2],
+        );
+    } else if let Some(error) = model.background_failures.get(&link.url) {
+        render_column_message(" preview ", error, frame, layout[2]);
+    } else if matches!(link.kind, LinkKind::Fragment | LinkKind::Download)
+        || model.selected_link_no_follow_rel().is_some()
+    {
+        render_column_message(" preview ", "not previewed", frame, layout[2]);
+    } else {
+        render_column_message(" preview ", "loading...", frame, layout[2]);
+    }
+}
+
+fn column_title(title: Option<&str>, url: &str) -> String {
+    format!(" {} ", title.unwrap_or(url))
+}
+
+fn render_column(
+    title: &str,
+    links: &[&Link],
+    selected: Option<usize>,
+    show_raw_urls: bool,
+    frame: &mut Frame,
+    rect: Rect,
+) {
+    let items: Vec<ListItem> = links
+        .iter()
+        .map(|link| {
+            link_list_item(
+                link,
+                &LinkListing {
+                    show_raw_urls,
+                    search: None,
+                    cache: None,
+                    marks: &[],
+                    visual: None,
+                },
+                false,
+            )
+        })
+        .collect();
+    let list = List::new(items)
+        .block(pane_block(title, PRIMARY_BORDER_COLOR).padding(Padding::new(0, 0, 1, 1)))
+        .style(Style::new().fg(BREADCRUMB_COLOR))
+        .highlight_style(Style::new().fg(PRIMARY_COLOR))
+        .direction(ListDirection::TopToBottom);
+
+    let mut state = ListState::default().with_selected(selected);
+    frame.render_stateful_widget(list, rect, &mut state);
+}
+
+fn render_column_message(title: &str, message: &str, frame: &mut Frame, rect: Rect) {
+    let p = Paragraph::new(message.to_string())
+        .block(pane_block(title, PRIMARY_BORDER_COLOR).padding(Padding::new(1, 0, 1, 1)))
+        .style(Style::new().fg(BREADCRUMB_COLOR))
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(p, rect);
+}
+
+// the path from the start page to the current one; when it doesn't fit, the
+// pages furthest back are elided
+fn render_breadcrumb(model: &Model, frame: &mut Frame, rect: Rect) {
+    let mut labels: Vec<String> = model
+        .history
+        .iter()
+        .map(|url| breadcrumb_label(model, url))
+        .collect();
+    // a failed fetch is a step in the history, but it doesn't replace the
+    // current page
+    let current = match model.results {
+        Ok(_) => breadcrumb_label(model, &model.page_details.url),
+        Err(_) => "error".to_string(),
+    };
+
+    let mut suffix = String::new();
+    if !model.forward_history.is_empty() {
+        suffix = format!("  (+{} forward)", model.forward_history.len());
+    }
+
+    let separator_width = BREADCRUMB_SEPARATOR.chars().count();
+    let available = (rect.width.saturating_sub(1) as usize)
+        .saturating_sub(current.chars().count() + suffix.chars().count());
+    let mut used: usize = labels
+        .iter()
+        .map(|l| l.chars().count() + separator_width)
+        .sum();
+    let mut elided = false;
+    while !labels.is_empty() && used > available {
+        let label = labels.remove(0);
+        used -= label.chars().count() + separator_width;
+        if !elided {
+            // room for the ellipsis standing in for the dropped pages
+            used += 1 + separator_width;
+            elided = true;
+        }
+    }
+
+    let dim = Style::new().fg(BREADCRUMB_COLOR);
+    let mut spans = vec![Span::from(" ")];
+    if elided {
+        spans.push(Span::styled(format!("…{}", BREADCRUMB_SEPARATOR), dim));
+    }
+    for label in labels {
+        spans.push(Span::styled(label, dim));
+        spans.push(Span::styled(BREADCRUMB_SEPARATOR, dim));
+    }
+    spans.push(Span::styled(current, Style::new().fg(URL_COLOR).bold()));
+    spans.push(Span::styled(suffix, dim));
+
+    frame.render_widget(Paragraph::new(Line::from(spans)), rect);
+}
+
+// pages are labelled by their title when known, falling back to their URL
+fn breadcrumb_label(model: &Model, url: &str) -> String {
+    let label = model
+        .results_cache
+        .get(url)
+        .and_then(|page| page.details.title.clone())
+        .unwrap_or_else(|| url.trim_start_matches("https://").to_string());
+
+    truncate_label(label, BREADCRUMB_LABEL_MAX_CHARS)
 }
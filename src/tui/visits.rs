@@ -0,0 +1,272 @@
+use crate::domain::Page;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+
+/// Every page visited in a session, as a tree: a page's children are the pages
+/// navigated to from it. Going back and then elsewhere starts a new branch,
+/// rather than discarding the one left.
+pub(crate) struct Visits {
+    nodes: Vec<Visit>,
+    current: usize,
+}
+
+struct Visit {
+    url: String,
+    parent: Option<usize>,
+    children: Vec<usize>,
+}
+
+/// A visit as laid out in the tree, with the guides to draw before it.
+pub(crate) struct VisitRow {
+    pub(crate) index: usize,
+    pub(crate) url: String,
+    pub(crate) prefix: String,
+}
+
+impl Visits {
+    pub(crate) fn new(url: &str) -> Self {
+        Self {
+            nodes: vec![Visit {
+                url: url.to_string(),
+                parent: None,
+                children: Vec::new(),
+            }],
+            current: 0,
+        }
+    }
+
+    pub(crate) fn current(&self) -> usize {
+        self.current
+    }
+
+    pub(crate) fn url(&self, index: usize) -> Option<&str> {
+        self.nodes.get(index).map(|v| v.url.as_str())
+    }
+
+    /// Records navigating from the current page to `url`. Visiting a page
+    /// that's already a child of the current one doesn't add a new branch.
+    pub(crate) fn visit(&mut self, url: &str) {
+        let existing = self.nodes[self.current]
+            .children
+            .iter()
+            .find(|&&child| self.nodes[child].url == url)
+            .copied();
+
+        self.current = existing.unwrap_or_else(|| {
+            self.nodes.push(Visit {
+                url: url.to_string(),
+                parent: Some(self.current),
+                children: Vec::new(),
+            });
+            let index = self.nodes.len() - 1;
+            self.nodes[self.current].children.push(index);
+            index
+        });
+    }
+
+    pub(crate) fn go_to_parent(&mut self) {
+        if let Some(parent) = self.nodes[self.current].parent {
+            self.current = parent;
+        }
+    }
+
+    pub(crate) fn go_to(&mut self, index: usize) {
+        if index < self.nodes.len() {
+            self.current = index;
+        }
+    }
+
+    /// Returns the URLs of the pages leading to the one at `index`, starting
+    /// from the first page of the session.
+    pub(crate) fn ancestor_urls(&self, index: usize) -> Vec<String> {
+        let mut urls = Vec::new();
+        let mut parent = self.nodes.get(index).and_then(|v| v.parent);
+        while let Some(p) = parent {
+            urls.push(self.nodes[p].url.clone());
+            parent = self.nodes[p].parent;
+        }
+        urls.reverse();
+
+        urls
+    }
+
+    /// Returns the visits in the order they appear in the tree.
+    pub(crate) fn rows(&self) -> Vec<VisitRow> {
+        let mut rows = Vec::new();
+        self.push_rows(0, "", "", &mut rows);
+        rows
+    }
+
+    fn push_rows(&self, index: usize, prefix: &str, indent: &str, rows: &mut Vec<VisitRow>) {
+        let visit = &self.nodes[index];
+        rows.push(VisitRow {
+            index,
+            url: visit.url.clone(),
+            prefix: prefix.to_string(),
+        });
+
+        for (i, &child) in visit.children.iter().enumerate() {
+            let last = i == visit.children.len() - 1;
+            let (branch, continuation) = if last {
+                ("└─ ", "   ")
+            } else {
+                ("├─ ", "│  ")
+            };
+            self.push_rows(
+                child,
+                &format!("{}{}", indent, branch),
+                &format!("{}{}", indent, continuation),
+                rows,
+            );
+        }
+    }
+
+    /// Renders the tree as a nested markdown list of links.
+    pub(crate) fn to_markdown(&self, cache: &HashMap<String, Page>) -> String {
+        let mut markdown = String::new();
+        self.push_markdown(0, 0, cache, &mut markdown);
+        markdown
+    }
+
+    fn push_markdown(
+        &self,
+        index: usize,
+        depth: usize,
+        cache: &HashMap<String, Page>,
+        markdown: &mut String,
+    ) {
+        let visit = &self.nodes[index];
+        let label = title(cache, &visit.url)
+            .unwrap_or(&visit.url)
+            .replace('[', "\\[")
+            .replace(']', "\\]");
+        markdown.push_str(&format!(
+            "{}- [{}]({})\n",
+            "  ".repeat(depth),
+            label,
+            visit.url
+        ));
+
+        for &child in &visit.children {
+            self.push_markdown(child, depth + 1, cache, markdown);
+        }
+    }
+
+    /// Renders the tree as JSON, each page being an object holding its URL,
+    /// title and children.
+    pub(crate) fn to_json(&self, cache: &HashMap<String, Page>) -> Value {
+        self.json_node(&self.nodes[0], cache)
+    }
+
+    fn json_node(&self, visit: &Visit, cache: &HashMap<String, Page>) -> Value {
+        let children: Vec<Value> = visit
+            .children
+            .iter()
+            .map(|&child| self.json_node(&self.nodes[child], cache))
+            .collect();
+
+        json!({
+            "url": visit.url,
+            "title": title(cache, &visit.url),
+            "children": children,
+        })
+    }
+}
+
+pub(crate) fn title<'a>(cache: &'a HashMap<String, Page>, url: &str) -> Option<&'a str> {
+    cache
+        .get(url)
+        .and_then(|page| page.details.title.as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a -> b -> c, then back to a and on to d
+    fn visits() -> Visits {
+        let mut visits = Visits::new("https://a.com/");
+        visits.visit("https://b.com/");
+        visits.visit("https://c.com/");
+        visits.go_to_parent();
+        visits.go_to_parent();
+        visits.visit("https://d.com/");
+        visits
+    }
+
+    #[test]
+    fn going_back_and_elsewhere_starts_a_new_branch() {
+        let visits = visits();
+
+        assert_eq!(visits.url(visits.current()), Some("https://d.com/"));
+        assert_eq!(visits.ancestor_urls(visits.current()), ["https://a.com/"]);
+        assert_eq!(
+            visits.ancestor_urls(2),
+            ["https://a.com/", "https://b.com/"]
+        );
+        assert!(visits.ancestor_urls(0).is_empty());
+    }
+
+    #[test]
+    fn visiting_a_child_again_reuses_it() {
+        let mut visits = visits();
+        visits.go_to_parent();
+        visits.visit("https://b.com/");
+
+        assert_eq!(visits.current(), 1);
+        assert_eq!(visits.rows().len(), 4);
+    }
+
+    #[test]
+    fn the_root_has_no_parent_to_go_to() {
+        let mut visits = Visits::new("https://a.com/");
+        visits.go_to_parent();
+        visits.go_to(5);
+
+        assert_eq!(visits.current(), 0);
+    }
+
+    #[test]
+    fn rows_are_laid_out_as_a_tree() {
+        let rows: Vec<String> = visits()
+            .rows()
+            .iter()
+            .map(|row| format!("{}{}", row.prefix, row.url))
+            .collect();
+
+        assert_eq!(
+            rows,
+            [
+                "https://a.com/",
+                "├─ https://b.com/",
+                "│  └─ https://c.com/",
+                "└─ https://d.com/",
+            ]
+        );
+    }
+
+    #[test]
+    fn markdown_uses_titles_when_known() {
+        let mut page = Page::with_links("https://b.com/", vec![]);
+        page.details.title = Some("Page [B]".to_string());
+        let cache = HashMap::from([("https://b.com/".to_string(), page)]);
+
+        assert_eq!(
+            visits().to_markdown(&cache),
+            "- [https://a.com/](https://a.com/)\n  \
+             - [Page \\[B\\]](https://b.com/)\n    \
+             - [https://c.com/](https://c.com/)\n  \
+             - [https://d.com/](https://d.com/)\n"
+        );
+    }
+
+    #[test]
+    fn json_nests_children() {
+        let json = visits().to_json(&HashMap::new());
+
+        assert_eq!(json["url"], "https://a.com/");
+        assert_eq!(json["title"], Value::Null);
+        assert_eq!(json["children"][0]["children"][0]["url"], "https://c.com/");
+        assert_eq!(json["children"][1]["url"], "https://d.com/");
+    }
+}
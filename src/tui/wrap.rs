@@ -0,0 +1,149 @@
+use ratatui::style::Style;
+use ratatui::text::{Line, Span};
+use unicode_width::UnicodeWidthChar;
+
+/// Wraps `lines` into rows at most `width` columns wide, breaking them between
+/// words (and within words too long for a row), while keeping the style of
+/// each span.
+///
+/// Wrapping up front rather than leaving it to `Paragraph` means the number of
+/// rows, and so how far a view can be scrolled, is known.
+pub(super) fn wrap_lines(lines: Vec<Line<'_>>, width: u16) -> Vec<Line<'static>> {
+    let width = usize::from(width.max(1));
+    let mut wrapped = Vec::new();
+
+    for line in lines {
+        let chars: Vec<(char, Style)> = line
+            .spans
+            .iter()
+            .flat_map(|span| span.content.chars().map(move |c| (c, span.style)))
+            .collect();
+
+        let mut rows = Vec::new();
+        let mut row: Vec<(char, Style)> = Vec::new();
+        let mut row_width = 0;
+        let mut start = 0;
+        while start < chars.len() {
+            let word_start = chars[start..]
+                .iter()
+                .position(|(c, _)| !c.is_whitespace())
+                .map_or(chars.len(), |i| start + i);
+            let word_end = chars[word_start..]
+                .iter()
+                .position(|(c, _)| c.is_whitespace())
+                .map_or(chars.len(), |i| word_start + i);
+            let (space, word) = (&chars[start..word_start], &chars[word_start..word_end]);
+            let (space_width, word_width) = (chars_width(space), chars_width(word));
+            start = word_end;
+
+            if row_width + space_width + word_width <= width {
+                row.extend_from_slice(space);
+                row.extend_from_slice(word);
+                row_width += space_width + word_width;
+            } else if word.is_empty() {
+                // whitespace at the end of the line that doesn't fit
+                break;
+            } else if word_width <= width && row_width > 0 {
+                rows.push(std::mem::take(&mut row));
+                row.extend_from_slice(word);
+                row_width = word_width;
+            } else {
+                for &(c, style) in space.iter().chain(word) {
+                    let char_width = c.width().unwrap_or(0);
+                    if row_width + char_width > width {
+                        while row.last().is_some_and(|(c, _)| c.is_whitespace()) {
+                            row.pop();
+                        }
+                        rows.push(std::mem::take(&mut row));
+                        row_width = 0;
+                        if c.is_whitespace() {
+                            continue;
+                        }
+                    }
+                    row.push((c, style));
+                    row_width += char_width;
+                }
+            }
+        }
+        rows.push(row);
+
+        wrapped.extend(
+            rows.into_iter()
+                .map(|row| Line::from(spans(&row)).style(line.style)),
+        );
+    }
+
+    wrapped
+}
+
+fn chars_width(chars: &[(char, Style)]) -> usize {
+    chars.iter().map(|(c, _)| c.width().unwrap_or(0)).sum()
+}
+
+// joins runs of characters with the same style back into spans
+fn spans(row: &[(char, Style)]) -> Vec<Span<'static>> {
+    row.chunk_by(|(_, a), (_, b)| a == b)
+        .map(|run| Span::styled(run.iter().map(|(c, _)| c).collect::<String>(), run[0].1))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::Stylize;
+
+    fn rows(lines: Vec<Line<'_>>, width: u16) -> Vec<String> {
+        wrap_lines(lines, width)
+            .iter()
+            .map(|line| line.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn lines_are_broken_between_words() {
+        assert_eq!(
+            rows(vec![Line::from("the quick brown fox jumps")], 10),
+            vec!["the quick", "brown fox", "jumps"]
+        );
+    }
+
+    #[test]
+    fn short_and_empty_lines_are_kept() {
+        assert_eq!(
+            rows(vec![Line::from("  indented"), Line::from("")], 20),
+            vec!["  indented", ""]
+        );
+    }
+
+    #[test]
+    fn words_longer_than_a_row_are_broken() {
+        assert_eq!(
+            rows(vec![Line::from("see https://example.com/a/b")], 10),
+            vec!["see https:", "//example.", "com/a/b"]
+        );
+    }
+
+    #[test]
+    fn trailing_whitespace_doesnt_add_rows() {
+        assert_eq!(rows(vec![Line::from("abcd  ")], 6), vec!["abcd  "]);
+        assert_eq!(rows(vec![Line::from("abcd      ")], 6), vec!["abcd"]);
+    }
+
+    #[test]
+    fn wide_characters_take_two_columns() {
+        assert_eq!(
+            rows(vec![Line::from("日本語 テキスト")], 7),
+            vec!["日本語", "テキス", "ト"]
+        );
+    }
+
+    #[test]
+    fn span_styles_are_kept() {
+        let line = Line::from(vec![Span::from("see "), "link".blue(), Span::from(" here")]);
+        let wrapped = wrap_lines(vec![line], 8);
+
+        assert_eq!(wrapped.len(), 2);
+        assert_eq!(wrapped[0].spans, vec![Span::from("see "), "link".blue()]);
+        assert_eq!(wrapped[1].spans, vec![Span::from("here")]);
+    }
+}